@@ -0,0 +1,24 @@
+// Builds illuc-cli and stages it as the sidecar `tauri.bundle.conf.json` registers, under
+// the target-triple name Tauri looks for.
+import { execFileSync } from "node:child_process";
+import { copyFileSync, mkdirSync } from "node:fs";
+import { dirname, join } from "node:path";
+import { fileURLToPath } from "node:url";
+
+const tauriDir = join(dirname(fileURLToPath(import.meta.url)), "..", "src-tauri");
+const rustcInfo = execFileSync("rustc", ["-vV"], { encoding: "utf8" });
+const triple = /^host: (\S+)$/m.exec(rustcInfo)?.[1];
+if (!triple) {
+  throw new Error("Unable to read the host target triple from `rustc -vV`.");
+}
+const ext = process.platform === "win32" ? ".exe" : "";
+
+execFileSync("cargo", ["build", "--release", "--bin", "illuc-cli"], {
+  cwd: tauriDir,
+  stdio: "inherit",
+});
+mkdirSync(join(tauriDir, "binaries"), { recursive: true });
+copyFileSync(
+  join(tauriDir, "target", "release", `illuc-cli${ext}`),
+  join(tauriDir, "binaries", `illuc-cli-${triple}${ext}`),
+);
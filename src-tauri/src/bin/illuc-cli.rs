@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(illuc_lib::run_cli())
+}
@@ -1,4 +1,5 @@
 use anyhow::{bail, Context};
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::path::PathBuf;
 #[cfg(target_os = "linux")]
@@ -7,6 +8,7 @@ use tauri::Manager;
 
 const SETTINGS_FILE_NAME: &str = "settings.toml";
 const THEMES_DIR_NAME: &str = "themes";
+const SKILLS_DIR_NAME: &str = "skills";
 const DEFAULT_SETTINGS: &str = include_str!("default_settings.toml");
 const DEFAULT_LIGHT_THEME: &str = include_str!("themes/light.toml");
 const DEFAULT_DARK_THEME: &str = include_str!("themes/dark.toml");
@@ -99,6 +101,11 @@ fn resolve_app_config_dir(app: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
         .with_context(|| "failed to resolve app config dir")
 }
 
+/// `<config-dir>/skills`: user skill packs illuc installs for every agent.
+pub(crate) fn user_skills_dir(app: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
+    Ok(resolve_app_config_dir(app)?.join(SKILLS_DIR_NAME))
+}
+
 pub fn ensure_user_settings_file(app: &tauri::AppHandle) -> anyhow::Result<PathBuf> {
     let config_dir = resolve_app_config_dir(app)?;
     let themes_dir = config_dir.join(THEMES_DIR_NAME);
@@ -152,6 +159,52 @@ pub fn load_selected_syntax_theme_name(
     Ok(extract_syntax_theme_name(&theme_table))
 }
 
+/// Reads a settings section (dotted keys such as `agents.restart` address nested tables).
+///
+/// A missing file or section yields the type's default; parse errors are logged and also fall
+/// back to the default so a typo in the settings file never blocks the app.
+pub(crate) fn load_settings_section<T>(app: &tauri::AppHandle, section: &str) -> T
+where
+    T: DeserializeOwned + Default,
+{
+    match try_load_settings_section(app, section) {
+        Ok(value) => value,
+        Err(error) => {
+            log::warn!("failed to load settings section [{section}]: {error:#}");
+            T::default()
+        }
+    }
+}
+
+/// Like [`load_settings_section`], but fails on an unreadable or invalid section instead of
+/// falling back to the default.
+pub(crate) fn try_load_settings_section<T>(
+    app: &tauri::AppHandle,
+    section: &str,
+) -> anyhow::Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    let settings_path = ensure_user_settings_file(app)?;
+    let content = std::fs::read_to_string(&settings_path)
+        .with_context(|| format!("failed to read {}", settings_path.display()))?;
+    let parsed: toml::Value = content
+        .parse()
+        .with_context(|| format!("failed to parse {}", settings_path.display()))?;
+
+    let mut value = &parsed;
+    for key in section.split('.') {
+        match value.get(key) {
+            Some(inner) => value = inner,
+            None => return Ok(T::default()),
+        }
+    }
+    value
+        .clone()
+        .try_into()
+        .with_context(|| format!("invalid [{section}] in {}", settings_path.display()))
+}
+
 pub(crate) fn load_theme_settings_snapshot(
     app: &tauri::AppHandle,
     default_theme_name: &str,
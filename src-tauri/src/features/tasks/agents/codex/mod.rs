@@ -1,3 +1,4 @@
+use crate::features::tasks::agents::AGENT_KIND_ENV;
 use crate::features::tasks::agents::{Agent, AgentCallbacks, AgentRuntime};
 use crate::features::tasks::TaskStatus;
 use crate::utils::pty::ReadHandle;
@@ -89,6 +90,60 @@ impl Agent for CodexAgent {
         callbacks: AgentCallbacks,
         rows: u16,
         cols: u16,
+    ) -> anyhow::Result<AgentRuntime> {
+        self.spawn(
+            worktree_path,
+            &["--full-auto", "resume", "--last"],
+            callbacks,
+            rows,
+            cols,
+        )
+    }
+
+    fn start_review(
+        &mut self,
+        worktree_path: &Path,
+        prompt: &str,
+        callbacks: AgentCallbacks,
+        rows: u16,
+        cols: u16,
+    ) -> anyhow::Result<AgentRuntime> {
+        // Writes outside the read-only sandbox, such as the review helper's, need an approval.
+        self.spawn(
+            worktree_path,
+            &[
+                "--sandbox",
+                "read-only",
+                "--ask-for-approval",
+                "on-request",
+                prompt,
+            ],
+            callbacks,
+            rows,
+            cols,
+        )
+    }
+
+    fn reset(&mut self, rows: usize, cols: usize) {
+        let mut state = self.state.lock();
+        state.screen = Screen::new(rows, cols);
+        state.last_output = None;
+        state.last_status = None;
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize) {
+        self.state.lock().screen.resize(rows, cols);
+    }
+}
+
+impl CodexAgent {
+    fn spawn(
+        &self,
+        worktree_path: &Path,
+        args: &[&str],
+        callbacks: AgentCallbacks,
+        rows: u16,
+        cols: u16,
     ) -> anyhow::Result<AgentRuntime> {
         let rows = rows.max(1);
         let cols = cols.max(1);
@@ -97,7 +152,8 @@ impl Agent for CodexAgent {
             let pty = spawn_wsl_pty(
                 worktree_path,
                 "codex",
-                &["--full-auto", "resume", "--last"],
+                args,
+                &[(AGENT_KIND_ENV, "codex")],
                 rows,
                 cols,
                 Some("xterm-256color"),
@@ -126,8 +182,9 @@ impl Agent for CodexAgent {
             let writer = Arc::new(Mutex::new(writer));
 
             let mut command = CommandBuilder::new("codex");
-            command.args(["--full-auto", "resume", "--last"]);
+            command.args(args);
             command.cwd(worktree_path);
+            command.env(AGENT_KIND_ENV, "codex");
 
             let child = pair
                 .slave
@@ -205,15 +262,4 @@ impl Agent for CodexAgent {
             master,
         })
     }
-
-    fn reset(&mut self, rows: usize, cols: usize) {
-        let mut state = self.state.lock();
-        state.screen = Screen::new(rows, cols);
-        state.last_output = None;
-        state.last_status = None;
-    }
-
-    fn resize(&mut self, rows: usize, cols: usize) {
-        self.state.lock().screen.resize(rows, cols);
-    }
 }
@@ -1,3 +1,4 @@
+use crate::features::tasks::agents::AGENT_KIND_ENV;
 use crate::features::tasks::agents::{Agent, AgentCallbacks, AgentRuntime};
 use crate::features::tasks::TaskStatus;
 use crate::utils::pty::{wrap_portable_child, wrap_portable_master};
@@ -8,6 +9,8 @@ use crate::utils::windows::build_wsl_command;
 use crate::utils::windows::build_wsl_process_command;
 #[cfg(target_os = "windows")]
 use crate::utils::windows::to_wsl_path;
+#[cfg(target_os = "windows")]
+use crate::utils::windows::wslenv_with;
 use anyhow::Context;
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use log::warn;
@@ -27,6 +30,14 @@ const DEFAULT_COLS: u16 = 80;
 
 const COPILOT_SESSION_DIR: &str = ".copilot/session-state";
 const COPILOT_LEGACY_SESSION_DIR: &str = ".copilot/history-session-state";
+/// Tools a review pass may use without asking; anything else needs the user's approval.
+const REVIEW_ALLOWED_TOOLS: &[&str] = &[
+    "shell(git diff)",
+    "shell(git log)",
+    "shell(git show)",
+    "shell(git status)",
+];
+const REVIEW_DENIED_TOOLS: &[&str] = &["write", "shell(git commit)", "shell(git push)"];
 
 #[derive(Clone)]
 pub struct CopilotAgent {
@@ -273,9 +284,6 @@ impl Agent for CopilotAgent {
         rows: u16,
         cols: u16,
     ) -> anyhow::Result<AgentRuntime> {
-        let pty_system = native_pty_system();
-        let rows = rows.max(1);
-        let cols = cols.max(1);
         let maybe_session_id = find_latest_session_id(worktree_path)?;
         let mut args = vec![
             "--allow-all-tools".to_string(),
@@ -286,6 +294,56 @@ impl Agent for CopilotAgent {
             args.push("--resume".to_string());
             args.push(session_id);
         }
+        self.spawn(worktree_path, &args, callbacks, rows, cols)
+    }
+
+    fn start_review(
+        &mut self,
+        worktree_path: &Path,
+        prompt: &str,
+        callbacks: AgentCallbacks,
+        rows: u16,
+        cols: u16,
+    ) -> anyhow::Result<AgentRuntime> {
+        // Only read-only git commands run unasked; file edits are denied outright.
+        let mut args = Vec::new();
+        for tool in REVIEW_ALLOWED_TOOLS {
+            args.push("--allow-tool".to_string());
+            args.push(tool.to_string());
+        }
+        for tool in REVIEW_DENIED_TOOLS {
+            args.push("--deny-tool".to_string());
+            args.push(tool.to_string());
+        }
+        args.push("--interactive".to_string());
+        args.push(prompt.to_string());
+        self.spawn(worktree_path, &args, callbacks, rows, cols)
+    }
+
+    fn reset(&mut self, rows: usize, cols: usize) {
+        let mut state = self.state.lock();
+        state.screen = Screen::new(rows, cols);
+        state.last_output = None;
+        state.last_status = None;
+    }
+
+    fn resize(&mut self, rows: usize, cols: usize) {
+        self.state.lock().screen.resize(rows, cols);
+    }
+}
+
+impl CopilotAgent {
+    fn spawn(
+        &self,
+        worktree_path: &Path,
+        args: &[String],
+        callbacks: AgentCallbacks,
+        rows: u16,
+        cols: u16,
+    ) -> anyhow::Result<AgentRuntime> {
+        let pty_system = native_pty_system();
+        let rows = rows.max(1);
+        let cols = cols.max(1);
         let pair = pty_system.openpty(PtySize {
             rows,
             cols,
@@ -306,7 +364,10 @@ impl Agent for CopilotAgent {
         #[cfg(target_os = "windows")]
         let command = {
             let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
-            build_wsl_command(worktree_path, "copilot", &arg_refs)
+            let mut command = build_wsl_command(worktree_path, "copilot", &arg_refs);
+            command.env(AGENT_KIND_ENV, "copilot");
+            command.env("WSLENV", wslenv_with(AGENT_KIND_ENV));
+            command
         };
 
         #[cfg(not(target_os = "windows"))]
@@ -314,6 +375,7 @@ impl Agent for CopilotAgent {
             let mut command = CommandBuilder::new("copilot");
             command.args(args.iter().map(|arg| arg.as_str()));
             command.cwd(worktree_path);
+            command.env(AGENT_KIND_ENV, "copilot");
             command
         };
 
@@ -392,15 +454,4 @@ impl Agent for CopilotAgent {
             master,
         })
     }
-
-    fn reset(&mut self, rows: usize, cols: usize) {
-        let mut state = self.state.lock();
-        state.screen = Screen::new(rows, cols);
-        state.last_output = None;
-        state.last_status = None;
-    }
-
-    fn resize(&mut self, rows: usize, cols: usize) {
-        self.state.lock().screen.resize(rows, cols);
-    }
 }
@@ -7,6 +7,9 @@ use std::sync::Arc;
 pub mod codex;
 pub mod copilot;
 
+/// Tells tools run by the agent (such as the review helper) which agent they run under.
+pub const AGENT_KIND_ENV: &str = "ILLUC_AGENT_KIND";
+
 pub struct AgentRuntime {
     pub child: Arc<Mutex<ChildHandle>>,
     pub writer: WriteHandle,
@@ -29,6 +32,17 @@ pub trait Agent: Send + Sync {
         cols: u16,
     ) -> anyhow::Result<AgentRuntime>;
 
+    /// Starts a new session, never resuming the task's conversation, that works on `prompt`
+    /// without permission to edit the worktree unless the user approves it in the terminal.
+    fn start_review(
+        &mut self,
+        worktree_path: &Path,
+        prompt: &str,
+        callbacks: AgentCallbacks,
+        rows: u16,
+        cols: u16,
+    ) -> anyhow::Result<AgentRuntime>;
+
     fn reset(&mut self, rows: usize, cols: usize);
 
     fn resize(&mut self, rows: usize, cols: usize);
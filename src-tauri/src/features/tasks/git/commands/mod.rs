@@ -1,7 +1,14 @@
 pub mod task_git_commit;
+pub mod task_git_diff_file_get;
 pub mod task_git_diff_get;
+pub mod task_git_diff_summary;
 pub mod task_git_diff_watch_start;
 pub mod task_git_diff_watch_stop;
+pub mod task_git_discard;
+pub mod task_git_file_lines_get;
 pub mod task_git_has_changes;
 pub mod task_git_list_branches;
+pub mod task_git_list_commits;
 pub mod task_git_push;
+pub mod task_git_stage;
+pub mod task_git_unstage;
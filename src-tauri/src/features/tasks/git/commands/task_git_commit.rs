@@ -2,8 +2,10 @@ use crate::commands::CommandResult;
 use crate::error::TaskError;
 use crate::features::tasks::events::emit_diff_changed;
 use crate::features::tasks::git::git_commit;
+use crate::features::tasks::review::gate::{enforce_review_gate, GatedAction};
 use crate::features::tasks::TaskManager;
 use serde::Deserialize;
+use std::path::PathBuf;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -28,9 +30,24 @@ pub async fn task_git_commit(
         return Err(TaskError::Message("Commit message is required.".into()).to_string());
     }
     let stage_all = req.stage_all.unwrap_or(true);
-    let worktree_path = manager
-        .worktree_path(task_id)
-        .map_err(|err| err.to_string())?;
+    let (worktree_path, base_repo_path) = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        (
+            PathBuf::from(&record.summary.worktree_path),
+            PathBuf::from(&record.summary.base_repo_path),
+        )
+    };
+    enforce_review_gate(
+        &app_handle,
+        &base_repo_path,
+        &worktree_path,
+        &task_id.to_string(),
+        GatedAction::Commit,
+    )
+    .map_err(|err| err.to_string())?;
     git_commit(worktree_path.as_path(), message, stage_all).map_err(|err| err.to_string())?;
     emit_diff_changed(&app_handle, task_id);
     Ok(())
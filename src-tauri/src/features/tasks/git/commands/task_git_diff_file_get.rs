@@ -0,0 +1,84 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::settings::load_settings_section;
+use crate::features::tasks::git::limits::DiffLimits;
+use crate::features::tasks::git::{
+    git_diff_for_mode, CommitRange, DiffContext, DiffFile, DiffMode, DiffScope,
+};
+use crate::features::tasks::TaskManager;
+use serde::Deserialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    pub path: String,
+    /// The path a renamed or copied file came from, so it is still diffed against it.
+    pub old_path: Option<String>,
+    pub ignore_whitespace: Option<bool>,
+    pub mode: Option<DiffMode>,
+    /// The commit or range a `commits` diff shows.
+    pub commits: Option<CommitRange>,
+    pub context_lines: Option<u32>,
+    pub interhunk_lines: Option<u32>,
+    /// Return every line, ignoring `[diff.limits]`.
+    pub full: Option<bool>,
+}
+
+pub type Response = DiffFile;
+
+#[tauri::command]
+pub async fn task_git_diff_file_get(
+    manager: tauri::State<'_, TaskManager>,
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let (worktree_path, base_commit) = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        (
+            PathBuf::from(&record.summary.worktree_path),
+            record.summary.base_commit.trim().to_string(),
+        )
+    };
+
+    let whitespace_flag = if req.ignore_whitespace.unwrap_or(false) {
+        Some("--ignore-all-space")
+    } else {
+        None
+    };
+    let mode = req.mode.unwrap_or(DiffMode::Worktree);
+    let scope = DiffScope {
+        mode,
+        base_commit: base_commit.as_str(),
+        commits: req.commits.as_ref(),
+    };
+    let context = DiffContext {
+        context_lines: req.context_lines,
+        interhunk_lines: req.interhunk_lines,
+    };
+    let mut paths = vec![req.path.clone()];
+    paths.extend(req.old_path.clone());
+    let mut file = git_diff_for_mode(
+        worktree_path.as_path(),
+        scope,
+        whitespace_flag,
+        &paths,
+        context,
+    )
+    .map_err(|err| err.to_string())?
+    .files
+    .into_iter()
+    .find(|file| file.path == req.path)
+    .ok_or_else(|| TaskError::Message(format!("{} has no changes.", req.path)).to_string())?;
+    if !req.full.unwrap_or(false) {
+        let limits: DiffLimits = load_settings_section(&app_handle, "diff.limits");
+        limits.apply(&mut file);
+    }
+    Ok(file)
+}
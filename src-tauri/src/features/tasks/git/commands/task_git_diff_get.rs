@@ -1,7 +1,12 @@
 use crate::commands::CommandResult;
 use crate::error::TaskError;
-use crate::features::tasks::git::DiffMode;
-use crate::features::tasks::git::{git_diff, stage_all};
+use crate::features::settings::load_settings_section;
+use crate::features::tasks::git::limits::DiffLimits;
+use crate::features::tasks::git::{
+    git_diff_for_mode, CommitRange, DiffContext, DiffMode, DiffScope,
+};
+use crate::features::tasks::review::anchor::relocate_task_threads_in_diff;
+use crate::features::tasks::review::viewed::{viewed_state, ReviewProgress};
 use crate::features::tasks::{DiffPayload, TaskManager};
 use log::warn;
 use serde::Deserialize;
@@ -14,6 +19,12 @@ pub struct Request {
     pub task_id: Uuid,
     pub ignore_whitespace: Option<bool>,
     pub mode: Option<DiffMode>,
+    /// The commit or range a `commits` diff shows.
+    pub commits: Option<CommitRange>,
+    pub context_lines: Option<u32>,
+    pub interhunk_lines: Option<u32>,
+    /// Cut each file at the `[diff.limits]`; off by default, so every line is returned.
+    pub limited: Option<bool>,
 }
 
 pub type Response = DiffPayload;
@@ -21,6 +32,7 @@ pub type Response = DiffPayload;
 #[tauri::command]
 pub async fn task_git_diff_get(
     manager: tauri::State<'_, TaskManager>,
+    app_handle: tauri::AppHandle,
     req: Request,
 ) -> CommandResult<Response> {
     let task_id = req.task_id;
@@ -35,41 +47,63 @@ pub async fn task_git_diff_get(
         )
     };
 
-    if let Err(err) = stage_all(worktree_path.as_path()) {
-        warn!(
-            "failed to stage files before diff for task {} at {}: {}",
-            task_id,
-            worktree_path.display(),
-            err
-        );
-    }
-
     let whitespace_flag = if req.ignore_whitespace.unwrap_or(false) {
         Some("--ignore-all-space")
     } else {
         None
     };
     let mode = req.mode.unwrap_or(DiffMode::Worktree);
-    match mode {
-        DiffMode::Worktree => {
-            let combined = git_diff(worktree_path.as_path(), "HEAD", whitespace_flag)
-                .map_err(|err| err.to_string())?;
-            Ok(DiffPayload {
-                task_id,
-                files: combined.files,
-            })
+    let scope = DiffScope {
+        mode,
+        base_commit: base_commit.as_str(),
+        commits: req.commits.as_ref(),
+    };
+    let context = DiffContext {
+        context_lines: req.context_lines,
+        interhunk_lines: req.interhunk_lines,
+    };
+    let mut files = git_diff_for_mode(
+        worktree_path.as_path(),
+        scope,
+        whitespace_flag,
+        &[],
+        context,
+    )
+    .map_err(|err| err.to_string())?
+    .files;
+    if matches!(mode, DiffMode::Branch) {
+        if let Err(err) = relocate_task_threads_in_diff(
+            worktree_path.as_path(),
+            &task_id.to_string(),
+            scope,
+            &files,
+        ) {
+            warn!(
+                "failed to relocate review threads for task {}: {}",
+                task_id, err
+            );
         }
-        DiffMode::Branch => {
-            let branch_diff = git_diff(
-                worktree_path.as_path(),
-                base_commit.as_str(),
-                whitespace_flag,
-            )
-            .map_err(|err| err.to_string())?;
-            Ok(DiffPayload {
-                task_id,
-                files: branch_diff.files,
-            })
+    }
+    if req.limited.unwrap_or(false) {
+        let limits: DiffLimits = load_settings_section(&app_handle, "diff.limits");
+        for file in &mut files {
+            limits.apply(file);
         }
     }
+    let (mut viewed_paths, mut progress) =
+        viewed_state(worktree_path.as_path(), &task_id.to_string(), &files).unwrap_or_else(|err| {
+            warn!("failed to read viewed files for task {}: {}", task_id, err);
+            (Vec::new(), ReviewProgress::default())
+        });
+    // Viewed marks are kept against worktree content, which a commit diff does not show.
+    if matches!(mode, DiffMode::Commits) {
+        viewed_paths.clear();
+        progress.files_viewed = 0;
+    }
+    Ok(DiffPayload {
+        task_id,
+        files,
+        viewed_paths,
+        progress,
+    })
 }
@@ -0,0 +1,86 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::git::{git_diff_stats_for_mode, CommitRange, DiffMode, DiffScope};
+use crate::features::tasks::review::anchor::relocate_task_threads_in_diff;
+use crate::features::tasks::review::viewed::{viewed_state, ReviewProgress};
+use crate::features::tasks::{DiffPayload, TaskManager};
+use log::warn;
+use serde::Deserialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    pub ignore_whitespace: Option<bool>,
+    pub mode: Option<DiffMode>,
+    /// The commit or range a `commits` diff shows.
+    pub commits: Option<CommitRange>,
+}
+
+/// The task's diff with stats but no lines; load each file with `task_git_diff_file_get`.
+pub type Response = DiffPayload;
+
+#[tauri::command]
+pub async fn task_git_diff_summary(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let (worktree_path, base_commit) = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        (
+            PathBuf::from(&record.summary.worktree_path),
+            record.summary.base_commit.trim().to_string(),
+        )
+    };
+
+    let whitespace_flag = if req.ignore_whitespace.unwrap_or(false) {
+        Some("--ignore-all-space")
+    } else {
+        None
+    };
+    let mode = req.mode.unwrap_or(DiffMode::Worktree);
+    let scope = DiffScope {
+        mode,
+        base_commit: base_commit.as_str(),
+        commits: req.commits.as_ref(),
+    };
+    let files = git_diff_stats_for_mode(worktree_path.as_path(), scope, whitespace_flag)
+        .map_err(|err| err.to_string())?
+        .files;
+    // Threads are relocated here, since per-file loads only see one file.
+    if matches!(mode, DiffMode::Branch) {
+        if let Err(err) = relocate_task_threads_in_diff(
+            worktree_path.as_path(),
+            &task_id.to_string(),
+            scope,
+            &files,
+        ) {
+            warn!(
+                "failed to relocate review threads for task {}: {}",
+                task_id, err
+            );
+        }
+    }
+    let (mut viewed_paths, mut progress) =
+        viewed_state(worktree_path.as_path(), &task_id.to_string(), &files).unwrap_or_else(|err| {
+            warn!("failed to read viewed files for task {}: {}", task_id, err);
+            (Vec::new(), ReviewProgress::default())
+        });
+    // Viewed marks are kept against worktree content, which a commit diff does not show.
+    if matches!(mode, DiffMode::Commits) {
+        viewed_paths.clear();
+        progress.files_viewed = 0;
+    }
+    Ok(DiffPayload {
+        task_id,
+        files,
+        viewed_paths,
+        progress,
+    })
+}
@@ -0,0 +1,59 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::git::{
+    git_file_lines, CommitRange, DiffMode, DiffScope, DiffSide, FileLines,
+};
+use crate::features::tasks::TaskManager;
+use serde::Deserialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    /// Path on the requested side; the old path of a renamed file for its old side.
+    pub path: String,
+    pub side: DiffSide,
+    pub mode: Option<DiffMode>,
+    /// The commit or range a `commits` diff shows.
+    pub commits: Option<CommitRange>,
+    /// First line to return, counting from 1; the start of the file when absent.
+    pub start: Option<u32>,
+    /// Last line to return; the end of the file when absent.
+    pub end: Option<u32>,
+}
+
+pub type Response = FileLines;
+
+#[tauri::command]
+pub async fn task_git_file_lines_get(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let (worktree_path, base_commit) = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        (
+            PathBuf::from(&record.summary.worktree_path),
+            record.summary.base_commit.trim().to_string(),
+        )
+    };
+    let scope = DiffScope {
+        mode: req.mode.unwrap_or(DiffMode::Worktree),
+        base_commit: base_commit.as_str(),
+        commits: req.commits.as_ref(),
+    };
+    git_file_lines(
+        worktree_path.as_path(),
+        scope,
+        req.side,
+        &req.path,
+        req.start,
+        req.end,
+    )
+    .map_err(|err| err.to_string())
+}
@@ -0,0 +1,35 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::git::{git_task_commits, CommitInfo};
+use crate::features::tasks::TaskManager;
+use serde::Deserialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+}
+
+/// Commits on the task's branch since its base commit, newest first.
+pub type Response = Vec<CommitInfo>;
+
+#[tauri::command]
+pub async fn task_git_list_commits(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let (worktree_path, base_commit) = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        (
+            PathBuf::from(&record.summary.worktree_path),
+            record.summary.base_commit.trim().to_string(),
+        )
+    };
+    git_task_commits(worktree_path.as_path(), base_commit.as_str()).map_err(|err| err.to_string())
+}
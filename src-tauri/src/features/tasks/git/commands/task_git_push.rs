@@ -2,6 +2,7 @@ use crate::commands::CommandResult;
 use crate::error::TaskError;
 use crate::features::tasks::events::emit_diff_changed;
 use crate::features::tasks::git::git_push;
+use crate::features::tasks::review::gate::{enforce_review_gate, GatedAction};
 use crate::features::tasks::TaskManager;
 use serde::Deserialize;
 use std::path::PathBuf;
@@ -25,7 +26,7 @@ pub async fn task_git_push(
     req: Request,
 ) -> CommandResult<Response> {
     let task_id = req.task_id;
-    let (worktree_path, branch_name) = {
+    let (worktree_path, branch_name, base_repo_path) = {
         let tasks = manager.inner.tasks.read();
         let record = tasks
             .get(&task_id)
@@ -33,8 +34,17 @@ pub async fn task_git_push(
         (
             PathBuf::from(&record.summary.worktree_path),
             record.summary.branch_name.clone(),
+            PathBuf::from(&record.summary.base_repo_path),
         )
     };
+    enforce_review_gate(
+        &app_handle,
+        &base_repo_path,
+        &worktree_path,
+        &task_id.to_string(),
+        GatedAction::Push,
+    )
+    .map_err(|err| err.to_string())?;
     let remote = req.remote.unwrap_or_else(|| "origin".to_string());
     let branch = req.branch.unwrap_or(branch_name);
     let set_upstream = req.set_upstream.unwrap_or(true);
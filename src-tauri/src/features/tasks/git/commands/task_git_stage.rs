@@ -0,0 +1,46 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::events::emit_diff_changed;
+use crate::features::tasks::git::stage::{stage_hunks, HunkSelection};
+use crate::features::tasks::git::DiffContext;
+use crate::features::tasks::TaskManager;
+use serde::Deserialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    pub path: String,
+    pub hunks: Vec<HunkSelection>,
+    /// Lines of context the diff was loaded with; hunk ids depend on them.
+    pub context_lines: Option<u32>,
+    pub interhunk_lines: Option<u32>,
+}
+
+pub type Response = ();
+
+#[tauri::command]
+pub async fn task_git_stage(
+    manager: tauri::State<'_, TaskManager>,
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let worktree_path = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        PathBuf::from(&record.summary.worktree_path)
+    };
+    let context = DiffContext {
+        context_lines: req.context_lines,
+        interhunk_lines: req.interhunk_lines,
+    };
+    stage_hunks(worktree_path.as_path(), &req.path, &req.hunks, context)
+        .map_err(|err| err.to_string())?;
+    emit_diff_changed(&app_handle, task_id);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use super::DiffFile;
+use serde::Deserialize;
+
+/// `[diff.limits]`: how much of one file's diff is sent to the diff view at once.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DiffLimits {
+    pub max_lines: usize,
+    pub max_bytes: usize,
+}
+
+impl Default for DiffLimits {
+    fn default() -> Self {
+        Self {
+            max_lines: 3000,
+            max_bytes: 512 * 1024,
+        }
+    }
+}
+
+impl DiffLimits {
+    /// Cuts the file's lines at the first line past either limit and marks it truncated. The
+    /// hunk that was cut short loses its id, so it cannot be staged or discarded half seen.
+    pub fn apply(&self, file: &mut DiffFile) {
+        let mut bytes = 0;
+        let cut = file.lines.iter().enumerate().position(|(index, line)| {
+            bytes += line.content.len();
+            index >= self.max_lines || bytes > self.max_bytes
+        });
+        let Some(cut) = cut else {
+            return;
+        };
+        let cut_hunk = file.lines[cut].hunk_id.clone();
+        file.lines.truncate(cut);
+        if cut_hunk.is_some() {
+            for line in file.lines.iter_mut().rev() {
+                if line.hunk_id != cut_hunk {
+                    break;
+                }
+                line.hunk_id = None;
+            }
+        }
+        file.truncated = true;
+    }
+}
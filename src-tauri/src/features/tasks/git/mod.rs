@@ -1,21 +1,98 @@
 pub mod commands;
+pub mod limits;
+pub mod stage;
+mod word_diff;
 
 use crate::error::{Result, TaskError};
 use crate::features::tasks::{DiffLine, DiffLineType};
+use chrono::{DateTime, Utc};
 use git2::{
-    BranchType, Cred, Delta, DiffFormat, DiffOptions, ErrorCode, FetchOptions, IndexAddOption,
-    PushOptions, RemoteCallbacks, Repository, Signature, Status, StatusOptions, WorktreeAddOptions,
+    AttrCheckFlags, AttrValue, BranchType, Cred, Delta, DiffFindOptions, DiffFormat, DiffOptions,
+    ErrorCode, FetchOptions, IndexAddOption, Patch, PushOptions, RemoteCallbacks, Repository,
+    Signature, Status, StatusOptions, WorktreeAddOptions,
 };
 use log::warn;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum DiffMode {
     Worktree,
     Branch,
+    /// Changes in the index relative to HEAD.
+    Staged,
+    /// Changes in the worktree relative to the index, untracked files included.
+    Unstaged,
+    /// One commit against its first parent, or a range of commits; see `CommitRange`.
+    Commits,
+}
+
+/// The commits a `Commits` diff compares.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitRange {
+    /// Start of the range, left out of it like in `git diff from..to`; the first parent of `to`
+    /// when absent, so the diff shows the single commit `to`.
+    pub from: Option<String>,
+    pub to: String,
+}
+
+/// What a task diff compares: its mode, plus the commits some modes need.
+#[derive(Debug, Clone, Copy)]
+pub struct DiffScope<'a> {
+    pub mode: DiffMode,
+    /// The task's base commit, which `Branch` diffs against.
+    pub base_commit: &'a str,
+    pub commits: Option<&'a CommitRange>,
+}
+
+/// A commit on a task's branch, with its change stats against its first parent.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub message: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub time: DateTime<Utc>,
+    pub parent_ids: Vec<String>,
+    pub files_changed: u32,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// Unchanged lines shown around each change and merged across between hunks; libgit2's
+/// defaults (3 and 0) when unset. Hunk ids depend on them, so staging needs the same values
+/// the diff was loaded with.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffContext {
+    pub context_lines: Option<u32>,
+    pub interhunk_lines: Option<u32>,
+}
+
+/// Which side of a diff to read a file from.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffSide {
+    Old,
+    New,
+}
+
+/// A range of a file's lines, for expanding diff context or showing the whole file.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileLines {
+    pub path: String,
+    /// Line number of the first entry in `lines`, counting from 1.
+    pub start: u32,
+    pub lines: Vec<String>,
+    /// Lines in the whole file; 0 when the file does not exist on that side.
+    pub total_lines: u32,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -24,6 +101,29 @@ pub struct DiffFile {
     pub path: String,
     pub status: String,
     pub lines: Vec<DiffLine>,
+    /// The index holds changes to the file relative to HEAD.
+    pub staged: bool,
+    /// The worktree has changes to the file that are not in the index, or the file is untracked.
+    pub unstaged: bool,
+    /// Where the file was renamed or copied from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// Git treats the content as binary, so the diff has no lines for it.
+    pub binary: bool,
+    /// Size in bytes before the change; absent for added files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_size: Option<u64>,
+    /// Size in bytes after the change; absent for deleted files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_size: Option<u64>,
+    /// The file mode changed, e.g. it became executable.
+    pub mode_changed: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    /// Marked `linguist-generated` in `.gitattributes`; the diff view collapses it by default.
+    pub generated: bool,
+    /// `lines` stops short of the whole diff because the file is over the `[diff.limits]`.
+    pub truncated: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -157,6 +257,25 @@ pub fn get_head_branch(path: &Path) -> Result<String> {
     Ok(head.shorthand().unwrap_or("HEAD").to_string())
 }
 
+/// Reads `user.name` and `user.email` from the repository's config (including the global and
+/// system levels), or from the global config alone when no repository is given.
+pub fn get_user_identity(path: Option<&Path>) -> Result<(Option<String>, Option<String>)> {
+    let mut config = match path {
+        Some(path) => open_repo(path)?.config(),
+        None => git2::Config::open_default(),
+    }
+    .map_err(map_git_err)?;
+    let snapshot = config.snapshot().map_err(map_git_err)?;
+    let read = |key: &str| {
+        snapshot
+            .get_string(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    Ok((read("user.name"), read("user.email")))
+}
+
 pub fn list_branches(path: &Path) -> Result<Vec<String>> {
     let repo = open_repo(path)?;
     let mut branches = Vec::new();
@@ -510,16 +629,6 @@ pub fn git_commit(repo: &Path, message: &str, stage_all: bool) -> Result<()> {
     Ok(())
 }
 
-pub fn stage_all(repo: &Path) -> Result<()> {
-    let repo = open_repo(repo)?;
-    let mut index = repo.index().map_err(map_git_err)?;
-    index
-        .add_all(["*"].iter(), IndexAddOption::DEFAULT, None)
-        .map_err(map_git_err)?;
-    index.write().map_err(map_git_err)?;
-    Ok(())
-}
-
 pub fn git_push(repo: &Path, remote_name: &str, branch: &str, set_upstream: bool) -> Result<()> {
     let repo = open_repo(repo)?;
     let config = repo.config().map_err(map_git_err)?;
@@ -649,48 +758,582 @@ fn map_line_type(origin: char) -> DiffLineType {
     }
 }
 
+/// Whether each changed path has staged and unstaged changes, read without touching the index.
+fn file_stage_states(repo: &Repository) -> Result<HashMap<String, (bool, bool)>> {
+    let staged_mask = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    let unstaged_mask = Status::WT_NEW
+        | Status::WT_MODIFIED
+        | Status::WT_DELETED
+        | Status::WT_RENAMED
+        | Status::WT_TYPECHANGE;
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options)).map_err(map_git_err)?;
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| {
+            let status = entry.status();
+            let path = entry.path()?.to_string();
+            Some((
+                path,
+                (
+                    status.intersects(staged_mask),
+                    status.intersects(unstaged_mask),
+                ),
+            ))
+        })
+        .collect())
+}
+
+/// Stage states for the files of a diff; none for a diff between two trees.
+fn diff_stage_states(
+    repo: &Repository,
+    tree_to_tree: bool,
+) -> Result<HashMap<String, (bool, bool)>> {
+    if tree_to_tree {
+        Ok(HashMap::new())
+    } else {
+        file_stage_states(repo)
+    }
+}
+
+fn diff_options(ignore_whitespace: bool, paths: &[String], context: DiffContext) -> DiffOptions {
+    let mut options = DiffOptions::new();
+    if let Some(lines) = context.context_lines {
+        options.context_lines(lines);
+    }
+    if let Some(lines) = context.interhunk_lines {
+        options.interhunk_lines(lines);
+    }
+    if !paths.is_empty() {
+        for path in paths {
+            options.pathspec(path);
+        }
+        options.disable_pathspec_match(true);
+    }
+    // Untracked files are diffed as additions without staging them, so the index is left as
+    // the user or agent arranged it.
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+    if ignore_whitespace {
+        options.ignore_whitespace(true);
+        options.ignore_whitespace_change(true);
+        options.ignore_whitespace_eol(true);
+    }
+    options
+}
+
+fn head_tree(repo: &Repository) -> Option<git2::Tree<'_>> {
+    repo.head().ok()?.peel_to_tree().ok()
+}
+
+/// The index relative to HEAD when `staged`, otherwise the worktree relative to the index.
+fn index_diff<'r>(
+    repo: &'r Repository,
+    staged: bool,
+    options: &mut DiffOptions,
+) -> Result<git2::Diff<'r>> {
+    let diff = if staged {
+        let tree = head_tree(repo);
+        repo.diff_tree_to_index(tree.as_ref(), None, Some(options))
+    } else {
+        repo.diff_index_to_workdir(None, Some(options))
+    };
+    diff.map_err(map_git_err)
+}
+
+/// Changes from `base_commit` to the worktree, limited to `paths` when any are given.
 pub fn git_diff(
     repo: &Path,
     base_commit: &str,
     ignore_whitespace: Option<&str>,
+    paths: &[String],
+    context: DiffContext,
 ) -> Result<DiffPayloadResult> {
     let repo = open_repo(repo)?;
+    let mut options = diff_options(ignore_whitespace.is_some(), paths, context);
+    let diff = worktree_diff(&repo, base_commit, &mut options)?;
+    let files = collect_diff_files(&repo, &diff, false)?;
+    Ok(DiffPayloadResult { files })
+}
+
+fn worktree_diff<'r>(
+    repo: &'r Repository,
+    base_commit: &str,
+    options: &mut DiffOptions,
+) -> Result<git2::Diff<'r>> {
     let base_object = repo.revparse_single(base_commit).map_err(map_git_err)?;
     let base_commit = base_object.peel_to_commit().map_err(map_git_err)?;
     let base_tree = base_commit.tree().map_err(map_git_err)?;
+    let mut diff = repo
+        .diff_tree_to_workdir_with_index(Some(&base_tree), Some(options))
+        .map_err(map_git_err)?;
+    detect_renames(&mut diff)?;
+    Ok(diff)
+}
 
-    let mut options = DiffOptions::new();
-    if ignore_whitespace.is_some() {
-        options.ignore_whitespace(true);
-        options.ignore_whitespace_change(true);
-        options.ignore_whitespace_eol(true);
+/// Renames and copies are detected for diffs against commits only; the staged and unstaged
+/// views keep each path separate, the way the index stages them.
+fn detect_renames(diff: &mut git2::Diff<'_>) -> Result<()> {
+    let mut find_options = DiffFindOptions::new();
+    find_options.renames(true).copies(true).for_untracked(true);
+    diff.find_similar(Some(&mut find_options))
+        .map_err(map_git_err)
+}
+
+fn rev_tree<'r>(repo: &'r Repository, rev: &str) -> Result<git2::Tree<'r>> {
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(map_git_err)
+}
+
+/// Trees at both ends of `range`; no old tree for a root commit shown on its own.
+fn commit_range_trees<'r>(
+    repo: &'r Repository,
+    range: &CommitRange,
+) -> Result<(Option<git2::Tree<'r>>, git2::Tree<'r>)> {
+    let to = repo
+        .revparse_single(&range.to)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(map_git_err)?;
+    let from_tree = match &range.from {
+        Some(from) => Some(rev_tree(repo, from)?),
+        None => match to.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(map_git_err)?),
+            Err(err) if err.code() == ErrorCode::NotFound => None,
+            Err(err) => return Err(map_git_err(err)),
+        },
+    };
+    let to_tree = to.tree().map_err(map_git_err)?;
+    Ok((from_tree, to_tree))
+}
+
+/// Changes between two commits; see `CommitRange`.
+pub fn git_commit_diff(
+    repo: &Path,
+    range: &CommitRange,
+    ignore_whitespace: Option<&str>,
+    paths: &[String],
+    context: DiffContext,
+) -> Result<DiffPayloadResult> {
+    let repo = open_repo(repo)?;
+    let mut options = diff_options(ignore_whitespace.is_some(), paths, context);
+    let diff = commit_diff(&repo, range, &mut options)?;
+    let files = collect_diff_files(&repo, &diff, true)?;
+    Ok(DiffPayloadResult { files })
+}
+
+fn commit_diff<'r>(
+    repo: &'r Repository,
+    range: &CommitRange,
+    options: &mut DiffOptions,
+) -> Result<git2::Diff<'r>> {
+    let (from_tree, to_tree) = commit_range_trees(repo, range)?;
+    let mut diff = repo
+        .diff_tree_to_tree(from_tree.as_ref(), Some(&to_tree), Some(options))
+        .map_err(map_git_err)?;
+    detect_renames(&mut diff)?;
+    Ok(diff)
+}
+
+/// Commits reachable from HEAD but not from `base_commit`, newest first.
+pub fn git_task_commits(repo: &Path, base_commit: &str) -> Result<Vec<CommitInfo>> {
+    let repo = open_repo(repo)?;
+    let base = repo
+        .revparse_single(base_commit)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(map_git_err)?;
+    let mut walk = repo.revwalk().map_err(map_git_err)?;
+    walk.push_head().map_err(map_git_err)?;
+    walk.hide(base.id()).map_err(map_git_err)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .map_err(map_git_err)?;
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        let commit = repo
+            .find_commit(oid.map_err(map_git_err)?)
+            .map_err(map_git_err)?;
+        let range = CommitRange {
+            from: None,
+            to: commit.id().to_string(),
+        };
+        let (from_tree, to_tree) = commit_range_trees(&repo, &range)?;
+        let mut diff = repo
+            .diff_tree_to_tree(from_tree.as_ref(), Some(&to_tree), None)
+            .map_err(map_git_err)?;
+        detect_renames(&mut diff)?;
+        let stats = diff.stats().map_err(map_git_err)?;
+        let author = commit.author();
+        let time = DateTime::from_timestamp(author.when().seconds(), 0).unwrap_or_default();
+        let short_id = commit
+            .as_object()
+            .short_id()
+            .ok()
+            .and_then(|id| id.as_str().map(str::to_string))
+            .unwrap_or_else(|| commit.id().to_string()[..7].to_string());
+        commits.push(CommitInfo {
+            id: commit.id().to_string(),
+            short_id,
+            summary: commit.summary().unwrap_or_default().to_string(),
+            message: commit.message().unwrap_or_default().to_string(),
+            author_name: author.name().unwrap_or_default().to_string(),
+            author_email: author.email().unwrap_or_default().to_string(),
+            time,
+            parent_ids: commit.parent_ids().map(|id| id.to_string()).collect(),
+            files_changed: stats.files_changed() as u32,
+            additions: stats.insertions() as u32,
+            deletions: stats.deletions() as u32,
+        });
+    }
+    Ok(commits)
+}
+
+/// Staged changes (index against HEAD) or unstaged changes (worktree against the index).
+pub fn git_index_diff(
+    repo: &Path,
+    staged: bool,
+    ignore_whitespace: Option<&str>,
+    paths: &[String],
+    context: DiffContext,
+) -> Result<DiffPayloadResult> {
+    let repo = open_repo(repo)?;
+    let mut options = diff_options(ignore_whitespace.is_some(), paths, context);
+    let diff = index_diff(&repo, staged, &mut options)?;
+    let files = collect_diff_files(&repo, &diff, false)?;
+    Ok(DiffPayloadResult { files })
+}
+
+fn missing_commits() -> TaskError {
+    TaskError::Message("Pick a commit or commit range to diff.".into())
+}
+
+/// The task diff `scope` asks for: worktree against HEAD, against the base commit for the
+/// whole branch, one side of the index, or between commits.
+pub fn git_diff_for_mode(
+    repo: &Path,
+    scope: DiffScope<'_>,
+    ignore_whitespace: Option<&str>,
+    paths: &[String],
+    context: DiffContext,
+) -> Result<DiffPayloadResult> {
+    match scope.mode {
+        DiffMode::Worktree => git_diff(repo, "HEAD", ignore_whitespace, paths, context),
+        DiffMode::Branch => git_diff(repo, scope.base_commit, ignore_whitespace, paths, context),
+        DiffMode::Staged => git_index_diff(repo, true, ignore_whitespace, paths, context),
+        DiffMode::Unstaged => git_index_diff(repo, false, ignore_whitespace, paths, context),
+        DiffMode::Commits => {
+            let range = scope.commits.ok_or_else(missing_commits)?;
+            git_commit_diff(repo, range, ignore_whitespace, paths, context)
+        }
     }
+}
+
+/// Each file of the diff `scope` asks for with its stats but without lines, hunk ids or word
+/// changes, which makes it much cheaper than `git_diff_for_mode` on large diffs.
+pub fn git_diff_stats_for_mode(
+    repo: &Path,
+    scope: DiffScope<'_>,
+    ignore_whitespace: Option<&str>,
+) -> Result<DiffPayloadResult> {
+    let repo = open_repo(repo)?;
+    let mut options = diff_options(ignore_whitespace.is_some(), &[], DiffContext::default());
+    let diff = match scope.mode {
+        DiffMode::Worktree => worktree_diff(&repo, "HEAD", &mut options)?,
+        DiffMode::Branch => worktree_diff(&repo, scope.base_commit, &mut options)?,
+        DiffMode::Staged => index_diff(&repo, true, &mut options)?,
+        DiffMode::Unstaged => index_diff(&repo, false, &mut options)?,
+        DiffMode::Commits => {
+            let range = scope.commits.ok_or_else(missing_commits)?;
+            commit_diff(&repo, range, &mut options)?
+        }
+    };
+    let tree_to_tree = matches!(scope.mode, DiffMode::Commits);
+    let files = collect_diff_stats(&repo, &diff, tree_to_tree)?;
+    Ok(DiffPayloadResult { files })
+}
 
-    let diff = repo
-        .diff_tree_to_workdir_with_index(Some(&base_tree), Some(&mut options))
+/// Lines `start..=end` (from 1, clamped to the file) of `path` on one side of the diff `scope`
+/// shows: HEAD, the base commit, the index or a commit on the old side, the worktree, the index
+/// or a commit on the new side. The whole file when no range is given.
+pub fn git_file_lines(
+    repo: &Path,
+    scope: DiffScope<'_>,
+    side: DiffSide,
+    path: &str,
+    start: Option<u32>,
+    end: Option<u32>,
+) -> Result<FileLines> {
+    check_repo_relative(path)?;
+    let repo = open_repo(repo)?;
+    let content = match (side, scope.mode) {
+        (DiffSide::Old, DiffMode::Worktree | DiffMode::Staged) => {
+            tree_file(&repo, &rev_tree(&repo, "HEAD")?, path)?
+        }
+        (DiffSide::Old, DiffMode::Branch) => {
+            tree_file(&repo, &rev_tree(&repo, scope.base_commit)?, path)?
+        }
+        (_, DiffMode::Commits) => {
+            let range = scope.commits.ok_or_else(missing_commits)?;
+            match (side, commit_range_trees(&repo, range)?) {
+                (DiffSide::Old, (Some(tree), _)) | (DiffSide::New, (_, tree)) => {
+                    tree_file(&repo, &tree, path)?
+                }
+                (DiffSide::Old, (None, _)) => None,
+            }
+        }
+        (DiffSide::Old, DiffMode::Unstaged) | (DiffSide::New, DiffMode::Staged) => {
+            let index = repo.index().map_err(map_git_err)?;
+            match index.get_path(Path::new(path), 0) {
+                Some(entry) => Some(
+                    repo.find_blob(entry.id)
+                        .map_err(map_git_err)?
+                        .content()
+                        .to_vec(),
+                ),
+                None => None,
+            }
+        }
+        (DiffSide::New, _) => {
+            let root = repo
+                .workdir()
+                .ok_or_else(|| TaskError::Message("Repository has no worktree.".into()))?;
+            let Some(full_path) = worktree_path_inside(root, path)? else {
+                return Err(TaskError::Message(format!(
+                    "{} is not a path inside the repository.",
+                    path
+                )));
+            };
+            match std::fs::symlink_metadata(&full_path) {
+                // Like git, show a symlink as its target instead of following it out of the repo.
+                Ok(metadata) if metadata.file_type().is_symlink() => Some(
+                    std::fs::read_link(&full_path)?
+                        .to_string_lossy()
+                        .into_owned()
+                        .into_bytes(),
+                ),
+                Ok(metadata) if metadata.is_file() => Some(std::fs::read(full_path)?),
+                _ => None,
+            }
+        }
+    };
+    let content = content.unwrap_or_default();
+    if content.iter().take(8000).any(|byte| *byte == 0) {
+        return Err(TaskError::Message(format!("{} is a binary file.", path)));
+    }
+    let text = String::from_utf8_lossy(&content);
+    let all_lines: Vec<&str> = text.lines().collect();
+    let total_lines = all_lines.len() as u32;
+    let start = start.unwrap_or(1).max(1);
+    let end = end.unwrap_or(total_lines).min(total_lines);
+    let lines = if start <= end {
+        all_lines[start as usize - 1..end as usize]
+            .iter()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Ok(FileLines {
+        path: path.to_string(),
+        start,
+        lines,
+        total_lines,
+    })
+}
+
+/// Fails unless `path` is relative and stays inside the repository.
+fn check_repo_relative(path: &str) -> Result<()> {
+    let inside = !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if inside {
+        Ok(())
+    } else {
+        Err(TaskError::Message(format!(
+            "{} is not a path inside the repository.",
+            path
+        )))
+    }
+}
+
+/// `path` under the worktree `root` with its directories resolved, or `None` when a symlinked
+/// directory leads out of the repository. The file itself is left unresolved.
+fn worktree_path_inside(root: &Path, path: &str) -> Result<Option<PathBuf>> {
+    let full_path = root.join(path);
+    let (Some(parent), Some(name)) = (full_path.parent(), full_path.file_name()) else {
+        return Ok(None);
+    };
+    let parent = match parent.canonicalize() {
+        Ok(parent) => parent,
+        // A missing directory holds no file to read; the caller sees the file as missing.
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Some(full_path)),
+        Err(err) => return Err(err.into()),
+    };
+    if parent.starts_with(root.canonicalize()?) {
+        Ok(Some(parent.join(name)))
+    } else {
+        Ok(None)
+    }
+}
+
+fn tree_file(repo: &Repository, tree: &git2::Tree<'_>, path: &str) -> Result<Option<Vec<u8>>> {
+    let entry = match tree.get_path(Path::new(path)) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(map_git_err(err)),
+    };
+    let blob = entry
+        .to_object(repo)
+        .and_then(|object| object.peel_to_blob())
         .map_err(map_git_err)?;
+    Ok(Some(blob.content().to_vec()))
+}
 
-    let mut files_by_path: HashMap<String, DiffFile> = HashMap::new();
-    let mut file_order: Vec<String> = Vec::new();
+/// The changed files of a diff, in delta order. Deltas for the same path, such as a deleted
+/// file and its untracked replacement, share one entry.
+#[derive(Default)]
+struct DiffFileSet {
+    files_by_path: HashMap<String, DiffFile>,
+    file_order: Vec<String>,
+}
 
-    for delta in diff.deltas() {
-        let status = map_delta_status(delta.status()).to_string();
-        let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
-            Some(path) => path.to_string_lossy().to_string(),
-            None => continue,
+impl DiffFileSet {
+    /// Adds the file `delta` changes, or updates its entry, and returns its path.
+    fn add_delta(
+        &mut self,
+        repo: &Repository,
+        delta: &git2::DiffDelta<'_>,
+        stage_states: &HashMap<String, (bool, bool)>,
+    ) -> Option<String> {
+        let status = match delta.status() {
+            // Relative to the base, a file git does not track yet is an addition.
+            Delta::Untracked => "A",
+            other => map_delta_status(other),
+        }
+        .to_string();
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path())?
+            .to_string_lossy()
+            .to_string();
+        let old_path = match delta.status() {
+            Delta::Renamed | Delta::Copied => delta
+                .old_file()
+                .path()
+                .map(|path| path.to_string_lossy().to_string()),
+            _ => None,
         };
-        let entry = files_by_path.entry(path.clone());
-        if let std::collections::hash_map::Entry::Vacant(vacant) = entry {
-            file_order.push(path.clone());
-            vacant.insert(DiffFile {
-                path,
-                status,
-                lines: Vec::new(),
-            });
-        } else if let Some(file) = files_by_path.get_mut(&path) {
+        let mode_changed = delta.old_file().exists()
+            && delta.new_file().exists()
+            && delta.old_file().mode() != delta.new_file().mode();
+        let old_size = side_size(repo, &delta.old_file());
+        let new_size = side_size(repo, &delta.new_file());
+        let generated = is_generated(repo, &path);
+        if let Some(file) = self.files_by_path.get_mut(&path) {
             file.status = status;
+            file.old_path = old_path;
+            file.mode_changed = mode_changed;
+            file.old_size = old_size;
+            file.new_size = new_size;
+        } else {
+            self.file_order.push(path.clone());
+            let (staged, unstaged) = stage_states.get(&path).copied().unwrap_or_default();
+            self.files_by_path.insert(
+                path.clone(),
+                DiffFile {
+                    path: path.clone(),
+                    status,
+                    lines: Vec::new(),
+                    staged,
+                    unstaged,
+                    old_path,
+                    binary: false,
+                    old_size,
+                    new_size,
+                    mode_changed,
+                    additions: 0,
+                    deletions: 0,
+                    generated,
+                    truncated: false,
+                },
+            );
         }
+        Some(path)
+    }
+
+    fn into_files(mut self) -> Vec<DiffFile> {
+        self.file_order
+            .iter()
+            .filter_map(|path| self.files_by_path.remove(path))
+            .collect()
+    }
+}
+
+/// Like `collect_diff_files`, but counts the added and deleted lines of each file instead of
+/// building them.
+fn collect_diff_stats(
+    repo: &Repository,
+    diff: &git2::Diff<'_>,
+    tree_to_tree: bool,
+) -> Result<Vec<DiffFile>> {
+    let stage_states = diff_stage_states(repo, tree_to_tree)?;
+    let mut set = DiffFileSet::default();
+    for index in 0..diff.deltas().len() {
+        let Some(delta) = diff.get_delta(index) else {
+            continue;
+        };
+        let Some(path) = set.add_delta(repo, &delta, &stage_states) else {
+            continue;
+        };
+        let patch = Patch::from_diff(diff, index).map_err(map_git_err)?;
+        let Some(file) = set.files_by_path.get_mut(&path) else {
+            continue;
+        };
+        // Loading the patch is what flags the delta as binary.
+        let binary = diff
+            .get_delta(index)
+            .is_some_and(|delta| delta.flags().is_binary());
+        if binary {
+            file.binary = true;
+        } else if let Some(patch) = patch {
+            let (_, additions, deletions) = patch.line_stats().map_err(map_git_err)?;
+            file.additions += additions as u32;
+            file.deletions += deletions as u32;
+        }
+    }
+    Ok(set.into_files())
+}
+
+/// The files of `diff` with their lines. A `tree_to_tree` diff between commits says nothing
+/// about the index or worktree, so its files are neither staged nor unstaged and its hunks get
+/// no ids to stage them by.
+fn collect_diff_files(
+    repo: &Repository,
+    diff: &git2::Diff<'_>,
+    tree_to_tree: bool,
+) -> Result<Vec<DiffFile>> {
+    let stage_states = diff_stage_states(repo, tree_to_tree)?;
+    let mut set = DiffFileSet::default();
+    for delta in diff.deltas() {
+        set.add_delta(repo, &delta, &stage_states);
     }
+    let stage_state = |path: &str| stage_states.get(path).copied().unwrap_or_default();
+    let DiffFileSet {
+        mut files_by_path,
+        mut file_order,
+    } = set;
 
     diff.print(DiffFormat::Patch, |delta, _hunk, line| {
         let path = match delta.new_file().path().or_else(|| delta.old_file().path()) {
@@ -699,27 +1342,52 @@ pub fn git_diff(
         };
         if !files_by_path.contains_key(&path) {
             file_order.push(path.clone());
+            let (staged, unstaged) = stage_state(&path);
             files_by_path.insert(
                 path.clone(),
                 DiffFile {
                     path: path.clone(),
                     status: "M".to_string(),
                     lines: Vec::new(),
+                    staged,
+                    unstaged,
+                    old_path: None,
+                    binary: false,
+                    old_size: None,
+                    new_size: None,
+                    mode_changed: false,
+                    additions: 0,
+                    deletions: 0,
+                    generated: is_generated(repo, &path),
+                    truncated: false,
                 },
             );
         }
         let content = String::from_utf8_lossy(line.content());
         let content = content.trim_end_matches(['\r', '\n']).to_string();
         if let Some(file) = files_by_path.get_mut(&path) {
+            // Content is only loaded while printing, so that is when binary files are known.
+            if delta.flags().is_binary() {
+                file.binary = true;
+                return true;
+            }
             let old_lineno = line.old_lineno().filter(|value| *value > 0);
             let new_lineno = line.new_lineno().filter(|value| *value > 0);
             let line_number_old = old_lineno.map(|value| value as u32);
             let line_number_new = new_lineno.map(|value| value as u32);
+            let line_type = map_line_type(line.origin());
+            match line_type {
+                DiffLineType::Add => file.additions += 1,
+                DiffLineType::Del => file.deletions += 1,
+                _ => {}
+            }
             file.lines.push(DiffLine {
-                line_type: map_line_type(line.origin()),
+                line_type,
                 content,
                 line_number_old,
                 line_number_new,
+                hunk_id: None,
+                change_ranges: None,
             });
         }
         true
@@ -728,12 +1396,95 @@ pub fn git_diff(
 
     let mut files = Vec::with_capacity(file_order.len());
     for path in file_order {
-        if let Some(file) = files_by_path.remove(&path) {
+        if let Some(mut file) = files_by_path.remove(&path) {
+            if !tree_to_tree {
+                assign_hunk_ids(&mut file);
+            }
+            word_diff::mark_changed_words(&mut file);
             files.push(file);
         }
     }
+    Ok(files)
+}
+
+/// Whether `.gitattributes` marks the file `linguist-generated`.
+fn is_generated(repo: &Repository, path: &str) -> bool {
+    let value = repo
+        .get_attr(
+            Path::new(path),
+            "linguist-generated",
+            AttrCheckFlags::default(),
+        )
+        .ok()
+        .flatten();
+    match AttrValue::from_string(value) {
+        AttrValue::True => true,
+        AttrValue::String(value) => value.eq_ignore_ascii_case("true"),
+        _ => false,
+    }
+}
 
-    Ok(DiffPayloadResult { files })
+/// Size of one side of a delta, read from the blob when the delta only carries its id.
+fn side_size(repo: &Repository, file: &git2::DiffFile<'_>) -> Option<u64> {
+    if !file.exists() {
+        return None;
+    }
+    if file.size() > 0 {
+        return Some(file.size());
+    }
+    if !file.id().is_zero() {
+        if let Ok(blob) = repo.find_blob(file.id()) {
+            return Some(blob.size() as u64);
+        }
+    }
+    let path = file.path()?;
+    let metadata = std::fs::metadata(repo.workdir()?.join(path)).ok()?;
+    Some(metadata.len())
+}
+
+/// Tags every line of each hunk with an id hashed from the file path and the hunk's lines, so
+/// the same hunk gets the same id in any diff that shows it and an edited hunk gets a new one.
+fn assign_hunk_ids(file: &mut DiffFile) {
+    let is_header = |line: &DiffLine| matches!(line.line_type, DiffLineType::Hunk);
+    let mut start = match file.lines.iter().position(is_header) {
+        Some(start) => start,
+        None => return,
+    };
+    while start < file.lines.len() {
+        let end = file.lines[start + 1..]
+            .iter()
+            .position(is_header)
+            .map_or(file.lines.len(), |offset| start + 1 + offset);
+        let mut content = file.path.as_bytes().to_vec();
+        for line in &file.lines[start..end] {
+            content.push(b'\n');
+            content.push(match line.line_type {
+                DiffLineType::Add => b'+',
+                DiffLineType::Del => b'-',
+                DiffLineType::Context => b' ',
+                DiffLineType::Hunk => b'@',
+                DiffLineType::Meta => b'\\',
+            });
+            content.extend_from_slice(line.content.as_bytes());
+        }
+        let id = git2::Oid::hash_object(git2::ObjectType::Blob, &content)
+            .map(|oid| oid.to_string()[..16].to_string())
+            .unwrap_or_default();
+        for line in &mut file.lines[start..end] {
+            line.hunk_id = Some(id.clone());
+        }
+        start = end;
+    }
+}
+
+/// Blob id of the file as it is in the worktree right now; the zero id when it was deleted.
+pub fn worktree_blob_id(repo: &Path, file_path: &str) -> Result<String> {
+    let path = repo.join(file_path);
+    if !path.is_file() {
+        return Ok(git2::Oid::zero().to_string());
+    }
+    let oid = git2::Oid::hash_file(git2::ObjectType::Blob, &path).map_err(map_git_err)?;
+    Ok(oid.to_string())
 }
 
 pub fn has_uncommitted_changes(repo: &Path) -> Result<bool> {
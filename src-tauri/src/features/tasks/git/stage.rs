@@ -0,0 +1,669 @@
+use super::{collect_diff_files, diff_options, head_tree, index_diff, map_git_err, open_repo};
+use super::{DiffContext, DiffFile};
+use crate::error::{Result, TaskError};
+use crate::features::tasks::{DiffLine, DiffLineType};
+use git2::build::CheckoutBuilder;
+use git2::{Index, IndexEntry, IndexTime, Oid, Repository};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// A hunk of a file's diff, picked by the id `git_diff` reports on its lines.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkSelection {
+    pub hunk_id: String,
+    /// Added or deleted lines of the hunk to include; the whole hunk when absent.
+    pub lines: Option<Vec<LineSelection>>,
+}
+
+/// A changed line, given by the line numbers its `DiffLine` carries.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineSelection {
+    pub line_number_old: Option<u32>,
+    pub line_number_new: Option<u32>,
+}
+
+/// Copies the selected changes of `path` from the worktree into the index. Hunk ids come from
+/// the unstaged diff; while nothing of the file is staged the worktree diff has the same ids.
+/// Ids from branch or commit diffs fail as stale.
+pub fn stage_hunks(
+    repo: &Path,
+    path: &str,
+    hunks: &[HunkSelection],
+    context: DiffContext,
+) -> Result<()> {
+    let repo = open_repo(repo)?;
+    let file = file_diff(&repo, path, false, context)?;
+    check_selection(&file, hunks)?;
+    let mut index = repo.index().map_err(map_git_err)?;
+    let index_entry = index.get_path(Path::new(path), 0);
+    let old = match &index_entry {
+        Some(entry) => blob_content(&repo, entry.id)?,
+        None => Vec::new(),
+    };
+    let new = read_worktree_file(&repo, path)?;
+    let rebuilt = apply_changes(&old, new.as_deref().unwrap_or_default(), &file, |line| {
+        is_selected(line, hunks)
+    })?;
+    if rebuilt.exists_after(index_entry.is_some(), new.is_some()) {
+        let mode = match &index_entry {
+            Some(entry) => entry.mode,
+            None => worktree_file_mode(&repo, path),
+        };
+        write_index_entry(&mut index, path, mode, &rebuilt.content)?;
+    } else {
+        index.remove_path(Path::new(path)).map_err(map_git_err)?;
+    }
+    index.write().map_err(map_git_err)
+}
+
+/// Takes the selected changes of `path` back out of the index, leaving the worktree alone.
+/// Hunk ids come from the staged diff.
+pub fn unstage_hunks(
+    repo: &Path,
+    path: &str,
+    hunks: &[HunkSelection],
+    context: DiffContext,
+) -> Result<()> {
+    let repo = open_repo(repo)?;
+    let file = file_diff(&repo, path, true, context)?;
+    check_selection(&file, hunks)?;
+    let head_entry = head_tree(&repo).and_then(|tree| tree.get_path(Path::new(path)).ok());
+    let old = match &head_entry {
+        Some(entry) => blob_content(&repo, entry.id())?,
+        None => Vec::new(),
+    };
+    let mut index = repo.index().map_err(map_git_err)?;
+    let index_entry = index.get_path(Path::new(path), 0);
+    let new = match &index_entry {
+        Some(entry) => blob_content(&repo, entry.id)?,
+        None => Vec::new(),
+    };
+    let rebuilt = apply_changes(&old, &new, &file, |line| !is_selected(line, hunks))?;
+    if rebuilt.exists_after(head_entry.is_some(), index_entry.is_some()) {
+        let mode = match (&index_entry, &head_entry) {
+            (Some(entry), _) => entry.mode,
+            (None, Some(entry)) => entry.filemode() as u32,
+            (None, None) => REGULAR_FILE_MODE,
+        };
+        write_index_entry(&mut index, path, mode, &rebuilt.content)?;
+    } else {
+        index.remove_path(Path::new(path)).map_err(map_git_err)?;
+    }
+    index.write().map_err(map_git_err)
+}
+
+/// Reverts the selected unstaged changes of `path` in the worktree to what the index holds.
+/// Hunk ids come from the unstaged diff. The file is written through git's checkout, so smudge
+/// and eol filters apply and a restored file gets its index mode back.
+pub fn discard_hunks(
+    repo: &Path,
+    path: &str,
+    hunks: &[HunkSelection],
+    context: DiffContext,
+) -> Result<()> {
+    let repo = open_repo(repo)?;
+    let file = file_diff(&repo, path, false, context)?;
+    check_selection(&file, hunks)?;
+    let index = repo.index().map_err(map_git_err)?;
+    let index_entry = index.get_path(Path::new(path), 0);
+    let old = match &index_entry {
+        Some(entry) => blob_content(&repo, entry.id)?,
+        None => Vec::new(),
+    };
+    let new = read_worktree_file(&repo, path)?;
+    let rebuilt = apply_changes(&old, new.as_deref().unwrap_or_default(), &file, |line| {
+        !is_selected(line, hunks)
+    })?;
+    if rebuilt.exists_after(index_entry.is_some(), new.is_some()) {
+        let mode = match &index_entry {
+            Some(entry) => entry.mode,
+            None => worktree_file_mode(&repo, path),
+        };
+        checkout_content(&repo, path, mode, &rebuilt.content)
+    } else {
+        let target = worktree_root(&repo)?.join(path);
+        if target.is_file() {
+            fs::remove_file(&target)?;
+        }
+        Ok(())
+    }
+}
+
+const REGULAR_FILE_MODE: u32 = 0o100644;
+const EXECUTABLE_FILE_MODE: u32 = 0o100755;
+
+fn worktree_root(repo: &Repository) -> Result<&Path> {
+    repo.workdir()
+        .ok_or_else(|| TaskError::Message("Repository has no worktree.".into()))
+}
+
+fn file_diff(
+    repo: &Repository,
+    path: &str,
+    staged: bool,
+    context: DiffContext,
+) -> Result<DiffFile> {
+    let mut options = diff_options(false, &[path.to_string()], context);
+    let diff = index_diff(repo, staged, &mut options)?;
+    collect_diff_files(repo, &diff, false)?
+        .into_iter()
+        .find(|file| file.path == path)
+        .ok_or_else(|| {
+            let side = if staged { "staged" } else { "unstaged" };
+            TaskError::Message(format!("{} has no {} changes.", path, side))
+        })
+}
+
+fn stale_diff(path: &str) -> TaskError {
+    TaskError::Message(format!(
+        "The diff of {} changed since it was loaded; reload it and try again.",
+        path
+    ))
+}
+
+fn is_change(line: &DiffLine) -> bool {
+    matches!(line.line_type, DiffLineType::Add | DiffLineType::Del)
+}
+
+fn line_matches(selection: &LineSelection, line: &DiffLine) -> bool {
+    selection.line_number_old == line.line_number_old
+        && selection.line_number_new == line.line_number_new
+}
+
+/// Fails when a selected hunk or line is not in the file's current diff, so a stale view never
+/// stages or discards content the user has not seen.
+fn check_selection(file: &DiffFile, hunks: &[HunkSelection]) -> Result<()> {
+    if hunks.is_empty() {
+        return Err(TaskError::Message("Select at least one hunk.".into()));
+    }
+    for hunk in hunks {
+        let changes: Vec<&DiffLine> = file
+            .lines
+            .iter()
+            .filter(|line| line.hunk_id.as_deref() == Some(hunk.hunk_id.as_str()))
+            .filter(|line| is_change(line))
+            .collect();
+        if changes.is_empty() {
+            return Err(stale_diff(&file.path));
+        }
+        if let Some(lines) = &hunk.lines {
+            let known = lines
+                .iter()
+                .all(|selection| changes.iter().any(|line| line_matches(selection, line)));
+            if !known {
+                return Err(stale_diff(&file.path));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_selected(line: &DiffLine, hunks: &[HunkSelection]) -> bool {
+    is_change(line)
+        && hunks.iter().any(|hunk| {
+            line.hunk_id.as_deref() == Some(hunk.hunk_id.as_str())
+                && hunk
+                    .lines
+                    .as_ref()
+                    .is_none_or(|lines| lines.iter().any(|selection| line_matches(selection, line)))
+        })
+}
+
+struct Rebuilt {
+    content: Vec<u8>,
+    applied_any: bool,
+    applied_all: bool,
+}
+
+impl Rebuilt {
+    /// Whether the file still exists once the changes are applied: a deletion only goes through
+    /// when all of it is applied, and an addition as soon as any of it is.
+    fn exists_after(&self, old_exists: bool, new_exists: bool) -> bool {
+        match (old_exists, new_exists) {
+            (true, true) => true,
+            (true, false) => !self.applied_all,
+            (false, _) => self.applied_any,
+        }
+    }
+}
+
+/// Rebuilds `old` with those changes of `file` that `apply` accepts and without the rest.
+/// Added lines are copied from `new` rather than from the diff, so line endings are kept.
+fn apply_changes(
+    old: &[u8],
+    new: &[u8],
+    file: &DiffFile,
+    apply: impl Fn(&DiffLine) -> bool,
+) -> Result<Rebuilt> {
+    let old_lines: Vec<&[u8]> = old.split_inclusive(|byte| *byte == b'\n').collect();
+    let new_lines: Vec<&[u8]> = new.split_inclusive(|byte| *byte == b'\n').collect();
+    let mut rebuilt = Rebuilt {
+        content: Vec::with_capacity(old.len().max(new.len())),
+        applied_any: false,
+        applied_all: true,
+    };
+    let mut next_old = 0;
+
+    fn push(content: &mut Vec<u8>, line: &[u8]) {
+        // A line that used to end the file without a newline needs one once more follows it.
+        if content.last().is_some_and(|byte| *byte != b'\n') {
+            content.push(b'\n');
+        }
+        content.extend_from_slice(line);
+    }
+    let copy_old_until = |content: &mut Vec<u8>, next_old: &mut usize, end: usize| -> Result<()> {
+        if end > old_lines.len() {
+            return Err(stale_diff(&file.path));
+        }
+        while *next_old < end {
+            push(content, old_lines[*next_old]);
+            *next_old += 1;
+        }
+        Ok(())
+    };
+
+    for line in &file.lines {
+        match line.line_type {
+            DiffLineType::Hunk => {
+                let (start, count) =
+                    hunk_old_range(&line.content).ok_or_else(|| stale_diff(&file.path))?;
+                // A hunk that removes nothing inserts after its start line.
+                let end = if count == 0 {
+                    start
+                } else {
+                    start.saturating_sub(1)
+                };
+                copy_old_until(&mut rebuilt.content, &mut next_old, end)?;
+            }
+            DiffLineType::Context => {
+                let number = line.line_number_old.ok_or_else(|| stale_diff(&file.path))?;
+                copy_old_until(&mut rebuilt.content, &mut next_old, number as usize)?;
+            }
+            DiffLineType::Del => {
+                let number = line.line_number_old.ok_or_else(|| stale_diff(&file.path))?;
+                copy_old_until(&mut rebuilt.content, &mut next_old, number as usize - 1)?;
+                if apply(line) {
+                    rebuilt.applied_any = true;
+                    next_old = number as usize;
+                } else {
+                    rebuilt.applied_all = false;
+                    copy_old_until(&mut rebuilt.content, &mut next_old, number as usize)?;
+                }
+            }
+            DiffLineType::Add => {
+                if apply(line) {
+                    let added = line
+                        .line_number_new
+                        .and_then(|number| new_lines.get(number as usize - 1))
+                        .ok_or_else(|| stale_diff(&file.path))?;
+                    rebuilt.applied_any = true;
+                    push(&mut rebuilt.content, added);
+                } else {
+                    rebuilt.applied_all = false;
+                }
+            }
+            DiffLineType::Meta => {}
+        }
+    }
+    copy_old_until(&mut rebuilt.content, &mut next_old, old_lines.len())?;
+    Ok(rebuilt)
+}
+
+/// Start and length of the old side of a `@@ -start,count +start,count @@` header.
+fn hunk_old_range(header: &str) -> Option<(usize, usize)> {
+    let range = header.strip_prefix("@@ -")?.split_whitespace().next()?;
+    let (start, count) = match range.split_once(',') {
+        Some((start, count)) => (start, count.parse().ok()?),
+        None => (range, 1),
+    };
+    Some((start.parse().ok()?, count))
+}
+
+fn blob_content(repo: &Repository, id: Oid) -> Result<Vec<u8>> {
+    let blob = repo.find_blob(id).map_err(map_git_err)?;
+    Ok(blob.content().to_vec())
+}
+
+/// The worktree file as git would store it, after the clean and eol filters. Diff lines are in
+/// this form too, so content rebuilt from both can go straight into a blob.
+fn read_worktree_file(repo: &Repository, path: &str) -> Result<Option<Vec<u8>>> {
+    let full_path = worktree_root(repo)?.join(path);
+    if !full_path.is_file() {
+        return Ok(None);
+    }
+    let id = repo.blob_path(&full_path).map_err(map_git_err)?;
+    Ok(Some(blob_content(repo, id)?))
+}
+
+/// Writes `content`, as stored in git, to `path` in the worktree with `mode`, leaving the
+/// repository's index alone.
+fn checkout_content(repo: &Repository, path: &str, mode: u32, content: &[u8]) -> Result<()> {
+    let id = repo.blob(content).map_err(map_git_err)?;
+    let mut index = Index::new().map_err(map_git_err)?;
+    index
+        .add(&index_entry(path, mode, id, content.len()))
+        .map_err(map_git_err)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout
+        .force()
+        .update_index(false)
+        .disable_pathspec_match(true)
+        .path(path);
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))
+        .map_err(map_git_err)
+}
+
+#[cfg(unix)]
+fn worktree_file_mode(repo: &Repository, path: &str) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    let executable = repo
+        .workdir()
+        .and_then(|root| fs::metadata(root.join(path)).ok())
+        .is_some_and(|metadata| metadata.permissions().mode() & 0o111 != 0);
+    if executable {
+        EXECUTABLE_FILE_MODE
+    } else {
+        REGULAR_FILE_MODE
+    }
+}
+
+#[cfg(not(unix))]
+fn worktree_file_mode(_repo: &Repository, _path: &str) -> u32 {
+    REGULAR_FILE_MODE
+}
+
+fn write_index_entry(index: &mut Index, path: &str, mode: u32, content: &[u8]) -> Result<()> {
+    let entry = index_entry(path, mode, Oid::zero(), content.len());
+    index.add_frombuffer(&entry, content).map_err(map_git_err)
+}
+
+fn index_entry(path: &str, mode: u32, id: Oid, size: usize) -> IndexEntry {
+    // Zeroed stat data makes git re-hash the worktree file instead of trusting the entry.
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: size as u32,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        discard_hunks, file_diff, stage_hunks, unstage_hunks, DiffContext, HunkSelection,
+        LineSelection,
+    };
+    use crate::error::Result;
+    use git2::{Repository, Signature};
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// A repository whose first commit holds `files`.
+    fn repo_with(name: &str, files: &[(&str, &str)]) -> Result<(PathBuf, Repository)> {
+        repo_with_config(name, &[], files)
+    }
+
+    /// Like `repo_with`, with `config` set before anything is committed.
+    fn repo_with_config(
+        name: &str,
+        config: &[(&str, &str)],
+        files: &[(&str, &str)],
+    ) -> Result<(PathBuf, Repository)> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!("illuc-stage-test-{name}-{nanos}"));
+        std::fs::create_dir_all(&root)?;
+        let repo = Repository::init(&root).map_err(anyhow::Error::from)?;
+        {
+            let mut repo_config = repo.config().map_err(anyhow::Error::from)?;
+            for (key, value) in config {
+                repo_config
+                    .set_str(key, value)
+                    .map_err(anyhow::Error::from)?;
+            }
+            let mut index = repo.index().map_err(anyhow::Error::from)?;
+            for (path, content) in files {
+                std::fs::write(root.join(path), content)?;
+                index
+                    .add_path(Path::new(path))
+                    .map_err(anyhow::Error::from)?;
+            }
+            index.write().map_err(anyhow::Error::from)?;
+            let tree_id = index.write_tree().map_err(anyhow::Error::from)?;
+            let tree = repo.find_tree(tree_id).map_err(anyhow::Error::from)?;
+            let signature =
+                Signature::now("illuc", "illuc@example.com").map_err(anyhow::Error::from)?;
+            repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+                .map_err(anyhow::Error::from)?;
+        }
+        Ok((root, repo))
+    }
+
+    fn whole_hunks(
+        repo: &Repository,
+        path: &str,
+        context: DiffContext,
+    ) -> Result<Vec<HunkSelection>> {
+        let file = file_diff(repo, path, false, context)?;
+        let mut ids: Vec<String> = file
+            .lines
+            .into_iter()
+            .filter_map(|line| line.hunk_id)
+            .collect();
+        ids.dedup();
+        Ok(ids
+            .into_iter()
+            .map(|hunk_id| HunkSelection {
+                hunk_id,
+                lines: None,
+            })
+            .collect())
+    }
+
+    fn staged_content(repo: &Repository, path: &str) -> Result<Option<String>> {
+        let mut index = repo.index().map_err(anyhow::Error::from)?;
+        // Staging wrote the index through its own handle.
+        index.read(true).map_err(anyhow::Error::from)?;
+        let Some(entry) = index.get_path(Path::new(path), 0) else {
+            return Ok(None);
+        };
+        let blob = repo.find_blob(entry.id).map_err(anyhow::Error::from)?;
+        Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+    }
+
+    #[test]
+    fn stages_lines_after_a_line_without_newline_at_eof() -> Result<()> {
+        let (root, repo) = repo_with("eof", &[("a.txt", "a\nb")])?;
+        std::fs::write(root.join("a.txt"), "a\nb\nc\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+        let only_c = vec![HunkSelection {
+            hunk_id: hunks[0].hunk_id.clone(),
+            lines: Some(vec![LineSelection {
+                line_number_old: None,
+                line_number_new: Some(3),
+            }]),
+        }];
+
+        stage_hunks(&root, "a.txt", &only_c, DiffContext::default())?;
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nb\nc\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stages_a_pure_insertion() -> Result<()> {
+        let (root, repo) = repo_with("insert", &[("a.txt", "a\nb\nc\n")])?;
+        std::fs::write(root.join("a.txt"), "a\nb\nx\nc\n")?;
+        let context = DiffContext {
+            context_lines: Some(0),
+            interhunk_lines: None,
+        };
+        let hunks = whole_hunks(&repo, "a.txt", context)?;
+
+        stage_hunks(&root, "a.txt", &hunks, context)?;
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nb\nx\nc\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stages_only_the_selected_lines_of_a_hunk() -> Result<()> {
+        let (root, repo) = repo_with("lines", &[("a.txt", "a\nold\nc\n")])?;
+        std::fs::write(root.join("a.txt"), "a\nb1\nb2\nc\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+        let selection = vec![HunkSelection {
+            hunk_id: hunks[0].hunk_id.clone(),
+            lines: Some(vec![
+                LineSelection {
+                    line_number_old: Some(2),
+                    line_number_new: None,
+                },
+                LineSelection {
+                    line_number_old: None,
+                    line_number_new: Some(3),
+                },
+            ]),
+        }];
+
+        stage_hunks(&root, "a.txt", &selection, DiffContext::default())?;
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nb2\nc\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stages_an_untracked_file() -> Result<()> {
+        let (root, repo) = repo_with("untracked", &[("a.txt", "a\n")])?;
+        std::fs::write(root.join("new.txt"), "one\ntwo\n")?;
+        let hunks = whole_hunks(&repo, "new.txt", DiffContext::default())?;
+
+        stage_hunks(&root, "new.txt", &hunks, DiffContext::default())?;
+        assert_eq!(
+            staged_content(&repo, "new.txt")?.as_deref(),
+            Some("one\ntwo\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stages_a_deletion() -> Result<()> {
+        let (root, repo) = repo_with("delete", &[("a.txt", "a\n"), ("gone.txt", "x\ny\n")])?;
+        std::fs::remove_file(root.join("gone.txt"))?;
+        let hunks = whole_hunks(&repo, "gone.txt", DiffContext::default())?;
+
+        stage_hunks(&root, "gone.txt", &hunks, DiffContext::default())?;
+        assert_eq!(staged_content(&repo, "gone.txt")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn discards_a_hunk_in_the_worktree() -> Result<()> {
+        let (root, repo) = repo_with("discard", &[("a.txt", "a\nb\nc\n")])?;
+        std::fs::write(root.join("a.txt"), "a\nchanged\nc\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+
+        discard_hunks(&root, "a.txt", &hunks, DiffContext::default())?;
+        assert_eq!(std::fs::read_to_string(root.join("a.txt"))?, "a\nb\nc\n");
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nb\nc\n")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unstages_the_selected_lines() -> Result<()> {
+        let (root, repo) = repo_with("unstage", &[("a.txt", "a\nb\nc\n")])?;
+        std::fs::write(root.join("a.txt"), "a\nB\nc\nd\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+        stage_hunks(&root, "a.txt", &hunks, DiffContext::default())?;
+
+        let staged = file_diff(&repo, "a.txt", true, DiffContext::default())?;
+        let hunk_id = staged
+            .lines
+            .iter()
+            .find_map(|line| line.hunk_id.clone())
+            .expect("staged hunk");
+        let only_d = vec![HunkSelection {
+            hunk_id,
+            lines: Some(vec![LineSelection {
+                line_number_old: None,
+                line_number_new: Some(4),
+            }]),
+        }];
+        unstage_hunks(&root, "a.txt", &only_d, DiffContext::default())?;
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nB\nc\n")
+        );
+        assert_eq!(std::fs::read_to_string(root.join("a.txt"))?, "a\nB\nc\nd\n");
+        Ok(())
+    }
+
+    #[test]
+    fn stages_and_discards_through_eol_filters() -> Result<()> {
+        let (root, repo) = repo_with_config(
+            "autocrlf",
+            &[("core.autocrlf", "true")],
+            &[("a.txt", "a\r\nb\r\nc\r\n")],
+        )?;
+        std::fs::write(root.join("a.txt"), "a\r\nB\r\nc\r\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+        stage_hunks(&root, "a.txt", &hunks, DiffContext::default())?;
+        assert_eq!(
+            staged_content(&repo, "a.txt")?.as_deref(),
+            Some("a\nB\nc\n")
+        );
+
+        std::fs::write(root.join("a.txt"), "a\r\nB\r\nC\r\n")?;
+        let hunks = whole_hunks(&repo, "a.txt", DiffContext::default())?;
+        discard_hunks(&root, "a.txt", &hunks, DiffContext::default())?;
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt"))?,
+            "a\r\nB\r\nc\r\n"
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn discarding_a_deletion_restores_the_file_mode() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let (root, repo) = repo_with("mode", &[("a.txt", "a\n"), ("run.sh", "echo hi\n")])?;
+        std::fs::set_permissions(root.join("run.sh"), std::fs::Permissions::from_mode(0o755))?;
+        let mut index = repo.index().map_err(anyhow::Error::from)?;
+        index
+            .add_path(Path::new("run.sh"))
+            .map_err(anyhow::Error::from)?;
+        index.write().map_err(anyhow::Error::from)?;
+        std::fs::remove_file(root.join("run.sh"))?;
+        std::fs::write(root.join("a.txt"), "edited\n")?;
+        let hunks = whole_hunks(&repo, "run.sh", DiffContext::default())?;
+
+        discard_hunks(&root, "run.sh", &hunks, DiffContext::default())?;
+        let metadata = std::fs::metadata(root.join("run.sh"))?;
+        assert_eq!(metadata.permissions().mode() & 0o111, 0o111);
+        assert_eq!(std::fs::read_to_string(root.join("run.sh"))?, "echo hi\n");
+        assert_eq!(std::fs::read_to_string(root.join("a.txt"))?, "edited\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use super::DiffFile;
+use crate::features::tasks::{DiffLineType, DiffRange};
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// Lines longer than this get no change ranges; diffing them costs more than the highlight
+/// is worth.
+const MAX_LINE_LENGTH: usize = 2000;
+
+/// Pairs each run of deleted lines with the run of added lines right after it, first with
+/// first, and marks the words that differ between the lines of each pair.
+pub(super) fn mark_changed_words(file: &mut DiffFile) {
+    let mut index = 0;
+    while index < file.lines.len() {
+        if !is_deleted(file, index) {
+            index += 1;
+            continue;
+        }
+        let deleted_start = index;
+        while is_deleted(file, index) {
+            index += 1;
+        }
+        let added_start = index;
+        while is_added(file, index) {
+            index += 1;
+        }
+        let pairs = (added_start - deleted_start).min(index - added_start);
+        for offset in 0..pairs {
+            let (old, new) = (deleted_start + offset, added_start + offset);
+            if let Some((old_ranges, new_ranges)) =
+                changed_ranges(&file.lines[old].content, &file.lines[new].content)
+            {
+                file.lines[old].change_ranges = Some(old_ranges);
+                file.lines[new].change_ranges = Some(new_ranges);
+            }
+        }
+    }
+}
+
+fn is_deleted(file: &DiffFile, index: usize) -> bool {
+    file.lines
+        .get(index)
+        .is_some_and(|line| matches!(line.line_type, DiffLineType::Del))
+}
+
+fn is_added(file: &DiffFile, index: usize) -> bool {
+    file.lines
+        .get(index)
+        .is_some_and(|line| matches!(line.line_type, DiffLineType::Add))
+}
+
+/// Ranges of `old` and `new` outside the words they share. None when the lines share no word,
+/// since highlighting all of both says nothing the line colours do not.
+fn changed_ranges(old: &str, new: &str) -> Option<(Vec<DiffRange>, Vec<DiffRange>)> {
+    if old.len() > MAX_LINE_LENGTH || new.len() > MAX_LINE_LENGTH {
+        return None;
+    }
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let old_offsets = utf16_offsets(&old_tokens);
+    let new_offsets = utf16_offsets(&new_tokens);
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let mut shares_word = false;
+    for op in capture_diff_slices(Algorithm::Myers, &old_tokens, &new_tokens) {
+        let old_span = op.old_range();
+        let new_span = op.new_range();
+        if let DiffOp::Equal { .. } = op {
+            shares_word |= old_tokens[old_span]
+                .iter()
+                .any(|token| !token.trim().is_empty());
+            continue;
+        }
+        push_range(
+            &mut old_ranges,
+            old_offsets[old_span.start],
+            old_offsets[old_span.end],
+        );
+        push_range(
+            &mut new_ranges,
+            new_offsets[new_span.start],
+            new_offsets[new_span.end],
+        );
+    }
+    if !shares_word || (old_ranges.is_empty() && new_ranges.is_empty()) {
+        return None;
+    }
+    Some((old_ranges, new_ranges))
+}
+
+/// Splits a line into runs of word characters, runs of whitespace and single other characters,
+/// so a changed argument or operator is highlighted on its own.
+fn tokenize(line: &str) -> Vec<&str> {
+    let class = |ch: char| {
+        if ch.is_alphanumeric() || ch == '_' {
+            0
+        } else if ch.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (index, ch) in line.char_indices() {
+        let current = class(ch);
+        if index > start && (previous != Some(current) || current == 2) {
+            tokens.push(&line[start..index]);
+            start = index;
+        }
+        previous = Some(current);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// Start of each token in UTF-16 code units, followed by the length of the whole line.
+fn utf16_offsets(tokens: &[&str]) -> Vec<u32> {
+    let mut offsets = Vec::with_capacity(tokens.len() + 1);
+    let mut offset = 0;
+    offsets.push(offset);
+    for token in tokens {
+        offset += token.encode_utf16().count() as u32;
+        offsets.push(offset);
+    }
+    offsets
+}
+
+fn push_range(ranges: &mut Vec<DiffRange>, start: u32, end: u32) {
+    if start == end {
+        return;
+    }
+    match ranges.last_mut() {
+        Some(last) if last.end == start => last.end = end,
+        _ => ranges.push(DiffRange { start, end }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_ranges, push_range, tokenize, utf16_offsets};
+    use crate::features::tasks::DiffRange;
+
+    fn range(start: u32, end: u32) -> DiffRange {
+        DiffRange { start, end }
+    }
+
+    #[test]
+    fn tokenize_splits_words_whitespace_and_punctuation() {
+        assert_eq!(
+            tokenize("let größe = f(x, y);"),
+            vec!["let", " ", "größe", " ", "=", " ", "f", "(", "x", ",", " ", "y", ")", ";"]
+        );
+        assert_eq!(tokenize("a→→b"), vec!["a", "→", "→", "b"]);
+        assert!(tokenize("").is_empty());
+    }
+
+    #[test]
+    fn utf16_offsets_count_surrogate_pairs_twice() {
+        assert_eq!(utf16_offsets(&["é", "😀", " ", "x"]), vec![0, 1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn changed_ranges_are_in_utf16_units() {
+        let (old, new) = changed_ranges("say 😀 hi", "say 😀 bye").unwrap();
+        assert_eq!(old, vec![range(7, 9)]);
+        assert_eq!(new, vec![range(7, 10)]);
+    }
+
+    #[test]
+    fn changed_ranges_skip_lines_without_a_shared_word() {
+        assert_eq!(changed_ranges("alpha beta", "gamma delta"), None);
+        assert_eq!(changed_ranges("same", "same"), None);
+    }
+
+    #[test]
+    fn adjacent_changes_merge_into_one_range() {
+        let (old, new) = changed_ranges("f(a, b)", "f(c; b)").unwrap();
+        assert_eq!(old, vec![range(2, 4)]);
+        assert_eq!(new, vec![range(2, 4)]);
+
+        let mut ranges = Vec::new();
+        push_range(&mut ranges, 0, 2);
+        push_range(&mut ranges, 2, 5);
+        push_range(&mut ranges, 5, 5);
+        push_range(&mut ranges, 7, 8);
+        assert_eq!(ranges, vec![range(0, 5), range(7, 8)]);
+    }
+}
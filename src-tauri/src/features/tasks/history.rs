@@ -0,0 +1,209 @@
+use crate::error::Result;
+use crate::features::tasks::{TaskStatus, TaskSummary};
+use crate::utils::fs::ensure_illuc_dir;
+use crate::utils::json_store::{self, StoreSchema};
+use crate::utils::store_writer;
+use chrono::{DateTime, Utc};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const HISTORY_VERSION: u32 = 1;
+const HISTORY_FILE: &str = "task-history.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHistoryStore {
+    pub version: u32,
+    pub tasks: HashMap<String, TaskHistoryEntry>,
+}
+
+impl Default for TaskHistoryStore {
+    fn default() -> Self {
+        Self {
+            version: HISTORY_VERSION,
+            tasks: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHistoryEntry {
+    pub task_id: String,
+    pub branch_name: String,
+    #[serde(default)]
+    pub transitions: Vec<StatusTransition>,
+    #[serde(default)]
+    pub restarts: Vec<RestartAttempt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusTransition {
+    pub from: TaskStatus,
+    pub to: TaskStatus,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartAttempt {
+    pub attempt: u32,
+    pub exit_code: i32,
+    pub failed_at: DateTime<Utc>,
+    pub delay_secs: u64,
+    pub restarted_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+const HISTORY_SCHEMA: StoreSchema = StoreSchema {
+    version: HISTORY_VERSION,
+    migrations: &[],
+};
+
+pub fn load_store(repo_root: &Path) -> Result<TaskHistoryStore> {
+    json_store::load(&history_path(repo_root)?, &HISTORY_SCHEMA)
+}
+
+fn history_path(repo_root: &Path) -> Result<PathBuf> {
+    let illuc_dir = ensure_illuc_dir(repo_root)?;
+    Ok(illuc_dir.join(HISTORY_FILE))
+}
+
+/// Applies `update` to the store while holding its lock, then writes the result back.
+pub fn update_store<R, F>(repo_root: &Path, update: F) -> Result<R>
+where
+    F: FnOnce(&mut TaskHistoryStore) -> Result<R>,
+{
+    json_store::update(&history_path(repo_root)?, &HISTORY_SCHEMA, update)
+}
+
+/// Applies `update` to the task's entry, creating it if needed.
+pub fn update_entry<F>(repo_root: &Path, task_id: &str, branch_name: &str, update: F) -> Result<()>
+where
+    F: FnOnce(&mut TaskHistoryEntry),
+{
+    update_store(repo_root, |store| {
+        let entry = store
+            .tasks
+            .entry(task_id.to_string())
+            .or_insert_with(|| TaskHistoryEntry {
+                task_id: task_id.to_string(),
+                branch_name: branch_name.to_string(),
+                transitions: Vec::new(),
+                restarts: Vec::new(),
+            });
+        entry.branch_name = branch_name.to_string();
+        update(entry);
+        Ok(())
+    })
+}
+
+/// Queues a status transition for the task's persisted timeline, logging failures. The write
+/// runs on the store writer thread, so callers may hold the task lock.
+pub fn record_transition(summary: &TaskSummary, from: TaskStatus, at: DateTime<Utc>) {
+    let repo_root = PathBuf::from(&summary.base_repo_path);
+    let task_id = summary.task_id.to_string();
+    let branch_name = summary.branch_name.clone();
+    let transition = StatusTransition {
+        from,
+        to: summary.status,
+        at,
+    };
+    store_writer::queue(move || {
+        if let Err(err) = update_entry(&repo_root, &task_id, &branch_name, |entry| {
+            entry.transitions.push(transition);
+        }) {
+            warn!(
+                "failed to record status transition for task {}: {}",
+                task_id, err
+            );
+        }
+    });
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHistoryStats {
+    pub seconds_by_status: HashMap<TaskStatus, u64>,
+    pub entries_by_status: HashMap<TaskStatus, u32>,
+}
+
+impl TaskHistoryEntry {
+    /// Sums time spent in each status and counts how often each status was entered.
+    ///
+    /// A segment only counts when the next transition starts from the status it ended in, so
+    /// gaps (e.g. the app was closed while the agent was working) are not billed. The last
+    /// segment is counted up to `open_until` when given.
+    pub fn stats(&self, open_until: Option<DateTime<Utc>>) -> TaskHistoryStats {
+        let mut stats = TaskHistoryStats::default();
+        for (index, transition) in self.transitions.iter().enumerate() {
+            *stats.entries_by_status.entry(transition.to).or_insert(0) += 1;
+            let end = match self.transitions.get(index + 1) {
+                Some(next) if next.from == transition.to => Some(next.at),
+                Some(_) => None,
+                None => open_until,
+            };
+            if let Some(end) = end {
+                let seconds = (end - transition.at).num_seconds().max(0) as u64;
+                let slot = stats.seconds_by_status.entry(transition.to).or_insert(0);
+                *slot = slot.saturating_add(seconds);
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StatusTransition, TaskHistoryEntry};
+    use crate::features::tasks::TaskStatus;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn stats_skip_segments_interrupted_by_untracked_gaps() {
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        let entry = TaskHistoryEntry {
+            task_id: "task".to_string(),
+            branch_name: "feature".to_string(),
+            transitions: vec![
+                StatusTransition {
+                    from: TaskStatus::Stopped,
+                    to: TaskStatus::Working,
+                    at: at(0),
+                },
+                StatusTransition {
+                    from: TaskStatus::Working,
+                    to: TaskStatus::AwaitingApproval,
+                    at: at(10),
+                },
+                // The app was closed while awaiting approval; the next run starts from Stopped.
+                StatusTransition {
+                    from: TaskStatus::Stopped,
+                    to: TaskStatus::Working,
+                    at: at(120),
+                },
+            ],
+            restarts: Vec::new(),
+        };
+
+        let stats = entry.stats(Some(at(125)));
+
+        assert_eq!(
+            stats.seconds_by_status.get(&TaskStatus::Working),
+            Some(&900)
+        );
+        assert_eq!(
+            stats.seconds_by_status.get(&TaskStatus::AwaitingApproval),
+            None
+        );
+        assert_eq!(stats.entries_by_status.get(&TaskStatus::Working), Some(&2));
+        assert_eq!(
+            stats.entries_by_status.get(&TaskStatus::AwaitingApproval),
+            Some(&1)
+        );
+    }
+}
@@ -1,6 +1,7 @@
 pub mod select_base_repo;
 pub mod task_create;
 pub mod task_discard;
+pub mod task_history_get;
 pub mod task_load_existing;
 pub mod task_open_worktree_in_vscode;
 pub mod task_open_worktree_terminal;
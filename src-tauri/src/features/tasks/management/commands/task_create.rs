@@ -6,9 +6,7 @@ use crate::features::tasks::git::{
     validate_git_repo,
 };
 use crate::features::tasks::worktree::managed_worktree_root;
-use crate::features::tasks::{
-    build_agent, AgentKind, TaskManager, TaskRecord, TaskStatus, TaskSummary,
-};
+use crate::features::tasks::{AgentKind, TaskManager, TaskRecord, TaskStatus, TaskSummary};
 use crate::utils::fs::ensure_directory;
 use crate::utils::path::normalize_path_string;
 use chrono::Utc;
@@ -106,19 +104,13 @@ pub async fn task_create(
         base_repo_path: normalize_path_string(&repo_root),
         base_commit,
         exit_code: None,
+        auto_restart: false,
+        restart_count: 0,
+        review_status: None,
     };
 
     let mut tasks = manager.inner.tasks.write();
-    tasks.insert(
-        task_id,
-        TaskRecord {
-            agent: build_agent(AgentKind::Codex),
-            agent_kind: AgentKind::Codex,
-            summary: summary.clone(),
-            runtime: None,
-            shell: None,
-        },
-    );
+    tasks.insert(task_id, TaskRecord::new(summary.clone()));
     drop(tasks);
     emit_status(&app_handle, &summary);
     Ok(summary)
@@ -1,6 +1,5 @@
 use crate::commands::CommandResult;
 use crate::error::TaskError;
-use crate::features::tasks::events::emit_status;
 use crate::features::tasks::git::{delete_branch, remove_worktree};
 use crate::features::tasks::{TaskManager, TaskStatus};
 use log::warn;
@@ -54,8 +53,7 @@ pub async fn task_discard(
             }
             let mut tasks = manager.inner.tasks.write();
             if let Some(record) = tasks.get_mut(&task_id) {
-                record.summary.status = TaskStatus::Stopped;
-                emit_status(&app_handle, &record.summary);
+                manager.set_status(record, TaskStatus::Stopped, &app_handle);
             }
         }
     }
@@ -101,9 +99,8 @@ pub async fn task_discard(
     {
         let mut tasks = manager.inner.tasks.write();
         if let Some(record) = tasks.get_mut(&task_id) {
-            record.summary.status = TaskStatus::Discarded;
             record.runtime = None;
-            emit_status(&app_handle, &record.summary);
+            manager.set_status(record, TaskStatus::Discarded, &app_handle);
         }
     }
 
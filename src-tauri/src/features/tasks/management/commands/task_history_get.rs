@@ -0,0 +1,66 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::git::get_repo_root;
+use crate::features::tasks::history::{
+    load_store, RestartAttempt, StatusTransition, TaskHistoryStats,
+};
+use crate::features::tasks::TaskManager;
+use crate::utils::store_writer;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub base_repo_path: String,
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub task_id: Uuid,
+    pub branch_name: Option<String>,
+    pub transitions: Vec<StatusTransition>,
+    pub restarts: Vec<RestartAttempt>,
+    pub stats: TaskHistoryStats,
+}
+
+#[tauri::command]
+pub async fn task_history_get(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let repo_root = get_repo_root(PathBuf::from(req.base_repo_path).as_path())
+        .map_err(|err| err.to_string())?;
+    // Transitions are written in the background; include the ones still queued.
+    store_writer::flush();
+    let store = load_store(&repo_root).map_err(|err| err.to_string())?;
+    let Some(entry) = store.tasks.get(&req.task_id.to_string()) else {
+        return Ok(Response {
+            task_id: req.task_id,
+            branch_name: None,
+            transitions: Vec::new(),
+            restarts: Vec::new(),
+            stats: TaskHistoryStats::default(),
+        });
+    };
+
+    // Only a live agent keeps accruing time in its current status.
+    let running = manager
+        .inner
+        .tasks
+        .read()
+        .get(&req.task_id)
+        .map(|record| record.runtime.is_some())
+        .unwrap_or(false);
+    let stats = entry.stats(running.then(Utc::now));
+    Ok(Response {
+        task_id: req.task_id,
+        branch_name: Some(entry.branch_name.clone()),
+        transitions: entry.transitions.clone(),
+        restarts: entry.restarts.clone(),
+        stats,
+    })
+}
@@ -7,9 +7,7 @@ use crate::features::tasks::git::{
 use crate::features::tasks::worktree::{
     clean_branch_name, format_title_from_branch, managed_worktree_root,
 };
-use crate::features::tasks::{
-    build_agent, AgentKind, TaskManager, TaskRecord, TaskStatus, TaskSummary,
-};
+use crate::features::tasks::{AgentKind, TaskManager, TaskRecord, TaskStatus, TaskSummary};
 use crate::utils::fs::ensure_directory;
 use crate::utils::path::normalize_path_string;
 use chrono::Utc;
@@ -101,17 +99,15 @@ pub async fn task_load_existing(
             base_repo_path: normalize_path_string(&repo_root),
             base_commit: base_repo_head.clone(),
             exit_code: None,
+            auto_restart: false,
+            restart_count: 0,
+            review_status: None,
         };
-        manager.inner.tasks.write().insert(
-            summary.task_id,
-            TaskRecord {
-                agent: build_agent(AgentKind::Codex),
-                agent_kind: AgentKind::Codex,
-                summary: summary.clone(),
-                runtime: None,
-                shell: None,
-            },
-        );
+        manager
+            .inner
+            .tasks
+            .write()
+            .insert(summary.task_id, TaskRecord::new(summary.clone()));
         emit_status(&app_handle, &summary);
         inserted.push(summary);
     }
@@ -1,15 +1,9 @@
 use crate::commands::CommandResult;
 use crate::error::TaskError;
-use crate::features::tasks::agents::{AgentCallbacks, AgentRuntime};
-use crate::features::tasks::events::emit_status;
-use crate::features::tasks::{
-    agent_label, build_agent, AgentKind, TaskManager, TaskRuntime, TaskStatus, TaskSummary,
-    DEFAULT_PTY_COLS, DEFAULT_PTY_ROWS, DEFAULT_SCREEN_COLS, DEFAULT_SCREEN_ROWS,
-};
-use anyhow::Context;
+use crate::features::settings::load_settings_section;
+use crate::features::tasks::review::auto_review::AutoReviewSettings;
+use crate::features::tasks::{AgentKind, RestartSettings, TaskManager, TaskSummary};
 use serde::Deserialize;
-use std::path::PathBuf;
-use std::sync::Arc;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -19,6 +13,9 @@ pub struct Request {
     pub cols: Option<u16>,
     pub rows: Option<u16>,
     pub agent: Option<AgentKind>,
+    pub auto_restart: Option<bool>,
+    /// Overrides `[review.auto] enabled` for this run.
+    pub auto_review: Option<bool>,
 }
 
 pub type Response = TaskSummary;
@@ -34,100 +31,27 @@ pub async fn task_start(
         cols,
         rows,
         agent,
+        auto_restart,
+        auto_review,
     } = req;
-    let requested_rows = rows.filter(|value| *value > 0);
-    let requested_cols = cols.filter(|value| *value > 0);
-    let screen_rows = requested_rows
-        .map(|value| value as usize)
-        .unwrap_or(DEFAULT_SCREEN_ROWS);
-    let screen_cols = requested_cols
-        .map(|value| value as usize)
-        .unwrap_or(DEFAULT_SCREEN_COLS);
-    let pty_rows = requested_rows.unwrap_or(DEFAULT_PTY_ROWS);
-    let pty_cols = requested_cols.unwrap_or(DEFAULT_PTY_COLS);
+    let restart_settings: RestartSettings = load_settings_section(&app_handle, "agents.restart");
+    let review_settings: AutoReviewSettings = load_settings_section(&app_handle, "review.auto");
     {
-        let tasks = manager.inner.tasks.read();
-        let record = tasks
-            .get(&task_id)
-            .ok_or_else(|| TaskError::NotFound.to_string())?;
-        if record.runtime.is_some() {
-            return Err(TaskError::AlreadyRunning.to_string());
-        }
-    }
-
-    let (worktree_path, title) = {
-        let tasks = manager.inner.tasks.read();
-        let record = tasks
-            .get(&task_id)
-            .ok_or_else(|| TaskError::NotFound.to_string())?;
-        (
-            PathBuf::from(&record.summary.worktree_path),
-            record.summary.title.clone(),
-        )
-    };
-
-    let status_manager = manager.inner().clone();
-    let status_app = app_handle.clone();
-    let output_manager = manager.inner().clone();
-    let output_app = app_handle.clone();
-    let exit_manager = manager.inner().clone();
-    let exit_app = app_handle.clone();
-    let callbacks = AgentCallbacks {
-        on_output: Arc::new(move |chunk: String| {
-            output_manager.handle_agent_output(task_id, chunk, &output_app);
-        }),
-        on_status: Arc::new(move |status: TaskStatus| {
-            status_manager.handle_agent_status(task_id, status, &status_app);
-        }),
-        on_exit: Arc::new(move |exit_code: i32| {
-            exit_manager.handle_agent_exit(task_id, exit_code, &exit_app);
-        }),
-    };
-
-    let agent_runtime = {
         let mut tasks = manager.inner.tasks.write();
         let record = tasks
             .get_mut(&task_id)
             .ok_or_else(|| TaskError::NotFound.to_string())?;
-        if let Some(requested_agent) = agent {
-            record.agent_kind = requested_agent;
-            record.agent = build_agent(requested_agent);
+        if record.runtime.is_some() {
+            return Err(TaskError::AlreadyRunning.to_string());
         }
-        record.summary.agent_kind = record.agent_kind;
-        let label = agent_label(record.agent_kind);
-        record.agent.reset(screen_rows, screen_cols);
-        record
-            .agent
-            .start(&worktree_path, callbacks, pty_rows, pty_cols)
-            .with_context(|| format!("failed to start {} for task {}", label, title))
-            .map_err(|err| err.to_string())?
-    };
-
-    let AgentRuntime {
-        child,
-        writer,
-        master,
-    } = agent_runtime;
-
-    {
-        let mut tasks = manager.inner.tasks.write();
-        let record = tasks
-            .get_mut(&task_id)
-            .ok_or_else(|| TaskError::NotFound.to_string())?;
-        record.summary.status = TaskStatus::Idle;
-        record.summary.started_at = Some(chrono::Utc::now());
-        record.summary.exit_code = None;
-        record.runtime = Some(TaskRuntime {
-            child: child.clone(),
-            writer: writer.clone(),
-            master: master.clone(),
-        });
-        emit_status(&app_handle, &record.summary);
+        record.restart_policy = restart_settings.policy(auto_restart);
+        record.summary.auto_restart = record.restart_policy.is_some();
+        record.summary.restart_count = 0;
+        record.auto_review =
+            review_settings.reviewer(auto_review, agent.unwrap_or(record.agent_kind));
     }
 
-    let tasks = manager.inner.tasks.read();
-    let record = tasks
-        .get(&task_id)
-        .ok_or_else(|| TaskError::NotFound.to_string())?;
-    Ok(record.summary.clone())
+    manager
+        .start_agent(task_id, agent, rows, cols, &app_handle)
+        .map_err(|err| err.to_string())
 }
@@ -1,6 +1,5 @@
 use crate::commands::CommandResult;
 use crate::error::TaskError;
-use crate::features::tasks::events::emit_status;
 use crate::features::tasks::{TaskManager, TaskStatus, TaskSummary};
 use log::warn;
 use serde::Deserialize;
@@ -43,7 +42,6 @@ pub async fn task_stop(
     let record = tasks
         .get_mut(&task_id)
         .ok_or_else(|| TaskError::NotFound.to_string())?;
-    record.summary.status = TaskStatus::Stopped;
-    emit_status(&app_handle, &record.summary);
+    manager.set_status(record, TaskStatus::Stopped, &app_handle);
     Ok(record.summary.clone())
 }
@@ -46,7 +46,13 @@ pub async fn task_terminal_resize(
             {
                 let mut tasks = manager.inner.tasks.write();
                 if let Some(record) = tasks.get_mut(&task_id) {
-                    record.agent.resize(req.rows as usize, req.cols as usize);
+                    record
+                        .terminal_agent_mut()
+                        .resize(req.rows as usize, req.cols as usize);
+                    record.terminal_size = Some(TerminalSize {
+                        cols: req.cols,
+                        rows: req.rows,
+                    });
                 }
             }
             Ok(())
@@ -24,24 +24,11 @@ pub async fn task_terminal_write(
 ) -> CommandResult<Response> {
     match req.kind {
         TerminalKind::Agent => {
-            let task_id = req.task_id;
-            let writer = {
-                let tasks = manager.inner.tasks.read();
-                let record = tasks
-                    .get(&task_id)
-                    .ok_or_else(|| TaskError::NotFound.to_string())?;
-                match &record.runtime {
-                    Some(runtime) => runtime.writer.clone(),
-                    None => return Err(TaskError::NotRunning.to_string()),
-                }
-            };
-            let mut writer_guard = writer.lock();
-            writer_guard
-                .write_all(req.data.as_bytes())
-                .with_context(|| "failed to write to terminal")
+            manager
+                .write_agent_input(req.task_id, req.data.as_bytes())
                 .map_err(|err| err.to_string())?;
-            if let Err(err) = writer_guard.flush() {
-                warn!("failed to flush terminal input for {}: {}", task_id, err);
+            if !is_terminal_report(&req.data) {
+                manager.record_terminal_activity(req.task_id);
             }
             Ok(())
         }
@@ -72,3 +59,48 @@ pub async fn task_terminal_write(
         }
     }
 }
+
+/// Whether `data` holds only replies the terminal sends on its own (focus changes, cursor
+/// position, status and device attribute reports, OSC/DCS query answers) rather than input.
+fn is_terminal_report(data: &str) -> bool {
+    let mut rest = data.as_bytes();
+    while !rest.is_empty() {
+        match report_len(rest) {
+            Some(len) => rest = &rest[len..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Length of the terminal report at the start of `data`, if it starts with one.
+fn report_len(data: &[u8]) -> Option<usize> {
+    match data {
+        [0x1b, b'[', rest @ ..] => {
+            let params = rest
+                .iter()
+                .take_while(|byte| matches!(byte, b'0'..=b'9' | b';' | b'?' | b'>' | b'$'))
+                .count();
+            let is_report = match rest.get(params)? {
+                b'I' | b'O' => params == 0,
+                b'c' => matches!(rest.first(), Some(b'?' | b'>')),
+                b'y' => rest[..params].contains(&b'$'),
+                b'R' | b'n' | b't' => params > 0,
+                _ => false,
+            };
+            is_report.then_some(2 + params + 1)
+        }
+        [0x1b, b']' | b'P', rest @ ..] => {
+            let end = rest
+                .iter()
+                .enumerate()
+                .find_map(|(index, byte)| match byte {
+                    0x07 => Some(index + 1),
+                    b'\\' if index > 0 && rest[index - 1] == 0x1b => Some(index + 1),
+                    _ => None,
+                })?;
+            Some(2 + end)
+        }
+        _ => None,
+    }
+}
@@ -2,6 +2,7 @@ mod agents;
 mod diff_watcher;
 pub(crate) mod events;
 pub mod git;
+pub mod history;
 pub mod management;
 pub mod models;
 mod repo;
@@ -13,9 +14,11 @@ pub(crate) use diff_watcher::DiffWatcher;
 pub use task_manager::TaskManager;
 pub(crate) use task_manager::{TaskRecord, TaskRuntime};
 
-pub use models::diff_payload::{DiffLine, DiffLineType};
+pub use models::diff_payload::{DiffLine, DiffLineType, DiffRange};
 pub use models::TerminalKind;
-pub use models::{AgentKind, BaseRepoInfo, DiffPayload, TaskStatus, TaskSummary};
+pub use models::{
+    AgentKind, BaseRepoInfo, DiffPayload, RestartPolicy, RestartSettings, TaskStatus, TaskSummary,
+};
 pub use repo::handle_select_base_repo;
 
 use crate::features::tasks::agents::codex::CodexAgent;
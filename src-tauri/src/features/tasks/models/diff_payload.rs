@@ -1,4 +1,5 @@
 use crate::features::tasks::git::DiffFile;
+use crate::features::tasks::review::viewed::ReviewProgress;
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -22,6 +23,20 @@ pub struct DiffLine {
     pub line_number_old: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub line_number_new: Option<u32>,
+    /// Identifies the hunk the line belongs to by its content, for staging it or its lines.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hunk_id: Option<String>,
+    /// Parts of a modified line that differ from the line it replaced or was replaced by.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_ranges: Option<Vec<DiffRange>>,
+}
+
+/// A span of `DiffLine::content` in UTF-16 code units, the way JavaScript indexes strings.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffRange {
+    pub start: u32,
+    pub end: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,4 +44,7 @@ pub struct DiffLine {
 pub struct DiffPayload {
     pub task_id: Uuid,
     pub files: Vec<DiffFile>,
+    /// Paths of the diff's files the reviewer marked as viewed at their current content.
+    pub viewed_paths: Vec<String>,
+    pub progress: ReviewProgress,
 }
@@ -1,6 +1,7 @@
 pub mod agent_kind;
 pub mod base_repo_info;
 pub mod diff_payload;
+pub mod restart_policy;
 pub mod task_status;
 pub mod task_summary;
 pub mod terminal_kind;
@@ -8,6 +9,7 @@ pub mod terminal_kind;
 pub use agent_kind::AgentKind;
 pub use base_repo_info::BaseRepoInfo;
 pub use diff_payload::DiffPayload;
+pub use restart_policy::{RestartPolicy, RestartSettings};
 pub use task_status::TaskStatus;
 pub use task_summary::TaskSummary;
 pub use terminal_kind::TerminalKind;
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_DELAY_SECS: u64 = 5;
+const DEFAULT_MAX_DELAY_SECS: u64 = 300;
+const DEFAULT_STABLE_AFTER_SECS: u64 = 600;
+
+/// How a crashed agent is brought back up.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartPolicy {
+    pub max_retries: u32,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    /// A run that lasted at least this long resets the retry streak.
+    pub stable_after_secs: u64,
+}
+
+impl RestartPolicy {
+    /// Exponential backoff for the given 1-based attempt, capped at `max_delay_secs`.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32);
+        let delay = self
+            .initial_delay_secs
+            .saturating_mul(1u64 << exponent)
+            .min(self.max_delay_secs.max(self.initial_delay_secs));
+        Duration::from_secs(delay)
+    }
+}
+
+/// `[agents.restart]` section of the user settings file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RestartSettings {
+    pub enabled: bool,
+    pub max_retries: u32,
+    pub initial_delay_secs: u64,
+    pub max_delay_secs: u64,
+    pub stable_after_secs: u64,
+}
+
+impl Default for RestartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_delay_secs: DEFAULT_INITIAL_DELAY_SECS,
+            max_delay_secs: DEFAULT_MAX_DELAY_SECS,
+            stable_after_secs: DEFAULT_STABLE_AFTER_SECS,
+        }
+    }
+}
+
+impl RestartSettings {
+    /// Resolves the effective policy, letting a per-task flag override `enabled`.
+    pub fn policy(&self, enabled_override: Option<bool>) -> Option<RestartPolicy> {
+        if !enabled_override.unwrap_or(self.enabled) || self.max_retries == 0 {
+            return None;
+        }
+        Some(RestartPolicy {
+            max_retries: self.max_retries,
+            initial_delay_secs: self.initial_delay_secs,
+            max_delay_secs: self.max_delay_secs,
+            stable_after_secs: self.stable_after_secs,
+        })
+    }
+}
@@ -19,4 +19,9 @@ pub struct TaskSummary {
     pub base_repo_path: String,
     pub base_commit: String,
     pub exit_code: Option<i32>,
+    pub auto_restart: bool,
+    pub restart_count: u32,
+    /// Status of the automatic review pass over the finished task; none when no pass runs.
+    /// `status` keeps what the task's own agent finished with.
+    pub review_status: Option<TaskStatus>,
 }
@@ -0,0 +1,457 @@
+use super::{
+    load_store, update_store, ReviewAnchor, ReviewLineType, ReviewRange, ReviewSide, ReviewThread,
+    TaskReviewEntry,
+};
+use crate::error::Result;
+use crate::features::tasks::git::{git_diff_for_mode, DiffContext, DiffFile, DiffScope};
+use crate::features::tasks::{DiffLine, DiffLineType};
+use std::cmp::Reverse;
+use std::path::Path;
+
+/// Lines of context stored on each side of an anchored line.
+pub(super) const ANCHOR_CONTEXT_LINES: usize = 3;
+
+pub(super) fn line_number(line: &DiffLine, side: ReviewSide) -> Option<u32> {
+    match side {
+        ReviewSide::Old => line.line_number_old,
+        ReviewSide::New => line.line_number_new,
+    }
+}
+
+/// The file's diff lines that exist on `side`, in order.
+pub(super) fn side_lines(file: &DiffFile, side: ReviewSide) -> Vec<&DiffLine> {
+    file.lines
+        .iter()
+        .filter(|line| {
+            matches!(
+                line.line_type,
+                DiffLineType::Add | DiffLineType::Del | DiffLineType::Context
+            ) && line_number(line, side).is_some()
+        })
+        .collect()
+}
+
+fn anchor_at(lines: &[&DiffLine], index: usize, range: ReviewRange) -> ReviewAnchor {
+    let before_start = index.saturating_sub(ANCHOR_CONTEXT_LINES);
+    let after_end = (index + 1 + ANCHOR_CONTEXT_LINES).min(lines.len());
+    let range_lines = if range.is_multi_line() {
+        lines
+            .iter()
+            .filter(|line| {
+                line_number(line, range.side)
+                    .is_some_and(|number| number >= range.start && number <= range.end)
+            })
+            .map(|line| line.content.clone())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    ReviewAnchor {
+        line: lines[index].content.clone(),
+        context_before: lines[before_start..index]
+            .iter()
+            .map(|line| line.content.clone())
+            .collect(),
+        context_after: lines[index + 1..after_end]
+            .iter()
+            .map(|line| line.content.clone())
+            .collect(),
+        lines: range_lines,
+    }
+}
+
+/// Captures the anchor for a new-side range straight from the worktree file.
+pub fn anchor_from_file(
+    worktree_root: &Path,
+    file_path: &str,
+    range: ReviewRange,
+) -> Option<ReviewAnchor> {
+    if range.side != ReviewSide::New {
+        return None;
+    }
+    let contents = std::fs::read_to_string(worktree_root.join(file_path)).ok()?;
+    let lines: Vec<&str> = contents.lines().collect();
+    let index = (range.end as usize).checked_sub(1)?;
+    let line = lines.get(index)?;
+    let range_lines = if range.is_multi_line() {
+        let start = (range.start as usize).saturating_sub(1).min(index);
+        lines[start..=index]
+            .iter()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let before_start = index.saturating_sub(ANCHOR_CONTEXT_LINES);
+    let after_end = (index + 1 + ANCHOR_CONTEXT_LINES).min(lines.len());
+    Some(ReviewAnchor {
+        line: line.trim_end_matches('\r').to_string(),
+        context_before: lines[before_start..index]
+            .iter()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect(),
+        context_after: lines[index + 1..after_end]
+            .iter()
+            .map(|line| line.trim_end_matches('\r').to_string())
+            .collect(),
+        lines: range_lines,
+    })
+}
+
+/// Counts context lines that still surround `index`, nearest first on each side.
+fn context_score(anchor: &ReviewAnchor, lines: &[&str], index: usize) -> usize {
+    let before = anchor
+        .context_before
+        .iter()
+        .rev()
+        .zip(lines[..index].iter().rev())
+        .take_while(|(expected, line)| expected.as_str() == **line)
+        .count();
+    let after = anchor
+        .context_after
+        .iter()
+        .zip(lines[index + 1..].iter())
+        .take_while(|(expected, line)| expected.as_str() == **line)
+        .count();
+    before + after
+}
+
+/// Index of the line holding the anchored text with the most context kept around it, the one
+/// nearest to `current` on a tie.
+fn best_match(
+    anchor: &ReviewAnchor,
+    lines: &[&str],
+    number_at: impl Fn(usize) -> Option<u32>,
+    current: u32,
+) -> Option<usize> {
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| **line == anchor.line)
+        .max_by_key(|(index, _)| {
+            let distance = number_at(*index).map_or(u32::MAX, |found| found.abs_diff(current));
+            (context_score(anchor, lines, *index), Reverse(distance))
+        })
+        .map(|(index, _)| index)
+}
+
+/// Finds the anchored line in the worktree file when the diff no longer shows it, such as once
+/// the change around it was reverted. Returns its old and new line numbers; the old one follows
+/// from how far the diff lines before it shifted the file.
+fn locate_in_file(
+    worktree_root: &Path,
+    file_path: &str,
+    anchor: &ReviewAnchor,
+    file: Option<&DiffFile>,
+    current: u32,
+) -> Option<(u32, u32)> {
+    let contents = std::fs::read_to_string(worktree_root.join(file_path)).ok()?;
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect();
+    let index = best_match(anchor, &lines, |index| Some(index as u32 + 1), current)?;
+    let new = index as u32 + 1;
+    let (mut last_old, mut last_new) = (0, 0);
+    for line in file.into_iter().flat_map(|file| &file.lines) {
+        if line.line_number_new.is_some_and(|number| number >= new) {
+            break;
+        }
+        last_old = line.line_number_old.unwrap_or(last_old).max(last_old);
+        last_new = line.line_number_new.unwrap_or(last_new).max(last_new);
+    }
+    Some(((last_old + new).checked_sub(last_new)?, new))
+}
+
+fn review_line_type(line_type: DiffLineType) -> ReviewLineType {
+    match line_type {
+        DiffLineType::Add => ReviewLineType::Add,
+        DiffLineType::Del => ReviewLineType::Del,
+        DiffLineType::Context => ReviewLineType::Context,
+        DiffLineType::Meta => ReviewLineType::Meta,
+        DiffLineType::Hunk => ReviewLineType::Hunk,
+    }
+}
+
+/// Moves the thread to where its anchored line is now, in the diff or else in the worktree
+/// file, or marks it outdated when the line is gone. Threads without an anchor get one
+/// captured at their current position. Returns whether the thread changed.
+fn relocate_thread(worktree_root: &Path, thread: &mut ReviewThread, files: &[DiffFile]) -> bool {
+    let Some(range) = thread.range else {
+        return false;
+    };
+    let side = range.side;
+    let current = range.end;
+    let file = files.iter().find(|file| file.path == thread.file_path);
+    let lines = file.map(|file| side_lines(file, side)).unwrap_or_default();
+
+    let Some(anchor) = thread.anchor.as_ref() else {
+        let position = lines.iter().position(|line| {
+            line.line_number_old == thread.line_number_old
+                && line.line_number_new == thread.line_number_new
+        });
+        return match position {
+            Some(index) => {
+                thread.anchor = Some(anchor_at(&lines, index, range));
+                true
+            }
+            None => false,
+        };
+    };
+
+    let contents: Vec<&str> = lines.iter().map(|line| line.content.as_str()).collect();
+    let best = best_match(
+        anchor,
+        &contents,
+        |index| line_number(lines[index], side),
+        current,
+    )
+    .map(|index| lines[index]);
+
+    if let Some(line) = best {
+        return move_thread(
+            thread,
+            line.line_number_old,
+            line.line_number_new,
+            review_line_type(line.line_type),
+        );
+    }
+    let in_file = match side {
+        ReviewSide::New => locate_in_file(worktree_root, &thread.file_path, anchor, file, current),
+        ReviewSide::Old => None,
+    };
+    match in_file {
+        Some((old, new)) => move_thread(thread, Some(old), Some(new), ReviewLineType::Context),
+        None => {
+            let changed = !thread.outdated;
+            thread.outdated = true;
+            changed
+        }
+    }
+}
+
+/// Puts the thread on the given line, keeping the span of its range; returns whether it moved.
+fn move_thread(
+    thread: &mut ReviewThread,
+    line_number_old: Option<u32>,
+    line_number_new: Option<u32>,
+    line_type: ReviewLineType,
+) -> bool {
+    let changed = thread.outdated
+        || thread.line_number_old != line_number_old
+        || thread.line_number_new != line_number_new;
+    if let Some(range) = thread.range {
+        let end = match range.side {
+            ReviewSide::Old => line_number_old,
+            ReviewSide::New => line_number_new,
+        };
+        if let Some(end) = end {
+            let span = range.end - range.start;
+            thread.range = Some(ReviewRange {
+                side: range.side,
+                start: end.saturating_sub(span).max(1),
+                end,
+            });
+        }
+    }
+    thread.line_number_old = line_number_old;
+    thread.line_number_new = line_number_new;
+    thread.line_type = line_type;
+    thread.outdated = false;
+    changed
+}
+
+/// Relocates every thread of the entry against `files`; returns whether anything changed.
+pub fn relocate_threads(
+    worktree_root: &Path,
+    entry: &mut TaskReviewEntry,
+    files: &[DiffFile],
+) -> bool {
+    let mut changed = false;
+    for thread in &mut entry.threads {
+        changed |= relocate_thread(worktree_root, thread, files);
+    }
+    changed
+}
+
+/// Paths of the files the task's threads are on.
+fn task_thread_paths(worktree_root: &Path, task_id: &str) -> Result<Vec<String>> {
+    let store = load_store(worktree_root)?;
+    let mut paths: Vec<String> = store
+        .tasks
+        .get(task_id)
+        .map(|entry| {
+            entry
+                .threads
+                .iter()
+                .map(|thread| thread.file_path.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Relocates the task's threads against the branch diff (base commit to worktree), writing
+/// the store only when a thread moved, became outdated or gained an anchor.
+pub fn relocate_task_threads(
+    worktree_root: &Path,
+    task_id: &str,
+    files: &[DiffFile],
+) -> Result<()> {
+    let store = load_store(worktree_root)?;
+    let Some(entry) = store.tasks.get(task_id) else {
+        return Ok(());
+    };
+    if !relocate_threads(worktree_root, &mut entry.clone(), files) {
+        return Ok(());
+    }
+    update_store(worktree_root, |store| {
+        if let Some(entry) = store.tasks.get_mut(task_id) {
+            relocate_threads(worktree_root, entry, files);
+        }
+        Ok(())
+    })
+}
+
+/// Relocates the task's threads against a fresh diff of the files in `files` they are on,
+/// together with the files those were renamed from. The caller's context and whitespace
+/// options do not apply: threads on lines they would hide would otherwise turn outdated.
+pub fn relocate_task_threads_in_diff(
+    worktree_root: &Path,
+    task_id: &str,
+    scope: DiffScope<'_>,
+    files: &[DiffFile],
+) -> Result<()> {
+    let thread_paths = task_thread_paths(worktree_root, task_id)?;
+    if thread_paths.is_empty() {
+        return Ok(());
+    }
+    let paths: Vec<String> = files
+        .iter()
+        .filter(|file| thread_paths.contains(&file.path))
+        .flat_map(|file| std::iter::once(file.path.clone()).chain(file.old_path.clone()))
+        .collect();
+    let files = if paths.is_empty() {
+        Vec::new()
+    } else {
+        git_diff_for_mode(worktree_root, scope, None, &paths, DiffContext::default())?.files
+    };
+    relocate_task_threads(worktree_root, task_id, &files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::relocate_thread;
+    use crate::features::tasks::git::DiffFile;
+    use crate::features::tasks::review::{
+        ReviewAnchor, ReviewCommentStatus, ReviewLineType, ReviewRange, ReviewScope, ReviewSide,
+        ReviewThread,
+    };
+    use crate::features::tasks::{DiffLine, DiffLineType};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn added(contents: &[&str], first_line: u32) -> DiffFile {
+        DiffFile {
+            path: "src/lib.rs".to_string(),
+            status: "M".to_string(),
+            lines: contents
+                .iter()
+                .zip(first_line..)
+                .map(|(content, number)| DiffLine {
+                    line_type: DiffLineType::Add,
+                    content: content.to_string(),
+                    line_number_old: None,
+                    line_number_new: Some(number),
+                    hunk_id: None,
+                    change_ranges: None,
+                })
+                .collect(),
+            staged: false,
+            unstaged: true,
+            old_path: None,
+            binary: false,
+            old_size: None,
+            new_size: None,
+            mode_changed: false,
+            additions: contents.len() as u32,
+            deletions: 0,
+            generated: false,
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn relocate_follows_moved_line_and_marks_removed_line_outdated() -> std::io::Result<()> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!("illuc-anchor-test-{nanos}"));
+        std::fs::create_dir_all(root.join("src"))?;
+        let mut thread = ReviewThread {
+            id: "thread".to_string(),
+            file_path: "src/lib.rs".to_string(),
+            line_number_old: None,
+            line_number_new: Some(2),
+            line_type: ReviewLineType::Add,
+            scope: ReviewScope::Line,
+            range: Some(ReviewRange {
+                side: ReviewSide::New,
+                start: 1,
+                end: 2,
+            }),
+            status: ReviewCommentStatus::Active,
+            comments: Vec::new(),
+            anchor: Some(ReviewAnchor {
+                line: "let b = 2;".to_string(),
+                context_before: vec!["let a = 1;".to_string()],
+                context_after: vec!["let c = 3;".to_string()],
+                lines: vec!["let a = 1;".to_string(), "let b = 2;".to_string()],
+            }),
+            outdated: false,
+        };
+
+        let moved = added(
+            &[
+                "// new",
+                "let b = 2;",
+                "let a = 1;",
+                "let b = 2;",
+                "let c = 3;",
+            ],
+            1,
+        );
+        assert!(relocate_thread(&root, &mut thread, &[moved]));
+        assert_eq!(thread.line_number_new, Some(4));
+        assert_eq!(
+            thread.range.map(|range| (range.start, range.end)),
+            Some((3, 4))
+        );
+        assert!(!thread.outdated);
+
+        std::fs::write(root.join("src/lib.rs"), "let a = 1;\nlet c = 3;\n")?;
+        let removed = added(&["let a = 1;", "let c = 3;"], 1);
+        assert!(relocate_thread(&root, &mut thread, &[removed]));
+        assert!(thread.outdated);
+        assert_eq!(thread.line_number_new, Some(4));
+
+        // Once the diff stops showing the line, the file still has it.
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "// old\nlet a = 1;\nlet b = 2;\nlet c = 3;\n",
+        )?;
+        let above = added(&["// old"], 1);
+        assert!(relocate_thread(&root, &mut thread, &[above]));
+        assert!(!thread.outdated);
+        assert_eq!(thread.line_number_old, Some(2));
+        assert_eq!(thread.line_number_new, Some(3));
+        assert_eq!(
+            thread.range.map(|range| (range.start, range.end)),
+            Some((2, 3))
+        );
+
+        std::fs::remove_dir_all(&root)
+    }
+}
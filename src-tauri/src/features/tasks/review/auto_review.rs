@@ -0,0 +1,41 @@
+use crate::features::tasks::{agent_label, AgentKind, TaskSummary};
+use serde::Deserialize;
+
+/// `[review.auto]`: a second agent reviews a task once its own agent finishes successfully.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AutoReviewSettings {
+    pub enabled: bool,
+    /// Agent that does the review; defaults to the task's own agent.
+    pub agent: Option<AgentKind>,
+}
+
+impl AutoReviewSettings {
+    /// The reviewer for a task run by `task_agent`, letting a per-task flag override `enabled`.
+    pub fn reviewer(
+        &self,
+        enabled_override: Option<bool>,
+        task_agent: AgentKind,
+    ) -> Option<AgentKind> {
+        enabled_override
+            .unwrap_or(self.enabled)
+            .then(|| self.agent.unwrap_or(task_agent))
+    }
+}
+
+/// Builds the instruction for a reviewer agent started on a finished task.
+pub fn build_prompt(summary: &TaskSummary, author: AgentKind) -> String {
+    format!(
+        "You are reviewing a finished task, not working on it. Use the illuc-review skill to \
+         review the changes {author} made for \"{title}\" on branch {branch}: run \
+         `git diff {base_commit}` to see everything since it branched off {base_branch}, \
+         including uncommitted work. Record each finding as a review thread with the skill's \
+         helper. Do not edit files, commit or push. When you are done, reply with a one-line \
+         summary of your review.",
+        author = agent_label(author),
+        title = summary.title,
+        branch = summary.branch_name,
+        base_commit = summary.base_commit,
+        base_branch = summary.base_branch,
+    )
+}
@@ -0,0 +1,654 @@
+//! `illuc-cli review ...`: the command line the review skills use to read and answer review
+//! threads from inside a task's worktree.
+
+use super::anchor::{anchor_from_file, ANCHOR_CONTEXT_LINES};
+use super::{
+    load_store, sorted_threads, update_store, ReviewAuthorKind, ReviewComment, ReviewCommentStatus,
+    ReviewLineType, ReviewRange, ReviewScope, ReviewSide, ReviewStore, ReviewSuggestion,
+    ReviewThread, TaskReviewEntry,
+};
+use crate::error::{Result, TaskError};
+use crate::features::tasks::agents::AGENT_KIND_ENV;
+use crate::features::tasks::git::get_repo_root;
+use crate::features::tasks::AgentKind;
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+const AUTHOR_ENV: &str = "ILLUC_REVIEW_AUTHOR";
+
+const USAGE: &str = "\
+Usage:
+  illuc-cli review list [--task <uuid>] [--status active,pending]
+  illuc-cli review show --thread <n> [--task <uuid>]
+  illuc-cli review add [--scope line|file|task] [--file <path>] [--line-new N|--line-old N] [--range-start N] [--line-type add|del|context|meta|hunk] [--status active|pending|resolved|wont-fix|closed] --message \"<text>\" [--suggestion \"<replacement>\"] [--author <name>] [--task <uuid>]
+  illuc-cli review comment --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]
+  illuc-cli review resolve --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]
+  illuc-cli review set-status --thread <n> --status <active|pending|resolved|wont-fix|closed> [--task <uuid>]
+
+Notes:
+  - Operates on <worktreeRoot>/.illuc/local-review.json (worktreeRoot is the git toplevel).
+  - Task id is usually the worktree folder name (UUID). If not provided, it is inferred.
+  - You can set a default author via env var ILLUC_REVIEW_AUTHOR.
+  - --range-start makes the thread cover lines N..line on the same side (a multi-line comment).
+  - --scope file comments on a whole file (--file only); --scope task on the task as a whole.
+  - --suggestion proposes replacement text for the thread's lines (new-side line threads only).
+";
+
+/// Runs the CLI and returns its exit code.
+pub fn run(args: &[String]) -> i32 {
+    match args.first().map(String::as_str) {
+        None | Some("--help" | "-h" | "help") => {
+            print!("{USAGE}");
+            0
+        }
+        Some("--version" | "-V") => {
+            println!("illuc-cli {}", env!("CARGO_PKG_VERSION"));
+            0
+        }
+        Some("review") => match run_review(&args[1..]) {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("error: {err}");
+                1
+            }
+        },
+        Some(other) => {
+            eprintln!("error: unknown command: {other}\n\n{USAGE}");
+            2
+        }
+    }
+}
+
+/// `--name value` options of one subcommand.
+struct Options {
+    values: HashMap<String, String>,
+}
+
+impl Options {
+    fn parse(args: &[String], allowed: &[&str]) -> Result<Self> {
+        let mut values = HashMap::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| allowed.contains(name))
+                .ok_or_else(|| message(format!("Unexpected argument: {arg}")))?;
+            let value = iter
+                .next()
+                .ok_or_else(|| message(format!("--{name} needs a value")))?;
+            values.insert(name.to_string(), value.clone());
+        }
+        Ok(Self { values })
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> Result<&str> {
+        self.get(name)
+            .ok_or_else(|| message(format!("--{name} is required")))
+    }
+
+    fn number(&self, name: &str) -> Result<Option<u32>> {
+        self.get(name)
+            .map(|value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| message(format!("--{name} must be a line number")))
+            })
+            .transpose()
+    }
+
+    /// Parses a kebab/lowercase value the same way the store serializes it.
+    fn choice<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>> {
+        self.get(name)
+            .map(|value| {
+                serde_json::from_value(Value::String(value.trim().to_string()))
+                    .map_err(|_| message(format!("--{name} has an invalid value: {value}")))
+            })
+            .transpose()
+    }
+
+    fn message(&self) -> Result<String> {
+        let body = self.required("message")?.trim();
+        if body.is_empty() {
+            return Err(message("--message cannot be empty".to_string()));
+        }
+        Ok(body.to_string())
+    }
+}
+
+fn message(text: String) -> TaskError {
+    TaskError::Message(text)
+}
+
+fn run_review(args: &[String]) -> Result<()> {
+    let Some(command) = args.first() else {
+        print!("{USAGE}");
+        return Ok(());
+    };
+    let rest = &args[1..];
+    let cwd = std::env::current_dir()?;
+    let worktree_root = get_repo_root(&cwd).unwrap_or(cwd);
+    let store_path = worktree_root.join(".illuc").join("local-review.json");
+    match command.as_str() {
+        "--help" | "-h" => {
+            print!("{USAGE}");
+            Ok(())
+        }
+        "list" => {
+            let options = Options::parse(rest, &["task", "status"])?;
+            let store = load_store(&worktree_root)?;
+            let task_id = infer_task_id(options.get("task"), &worktree_root, &store)?;
+            let statuses = options
+                .get("status")
+                .unwrap_or("active,pending")
+                .split(',')
+                .map(str::trim)
+                .filter(|status| !status.is_empty())
+                .map(|status| {
+                    serde_json::from_value(Value::String(status.to_string()))
+                        .map_err(|_| message(format!("--status has an invalid value: {status}")))
+                })
+                .collect::<Result<Vec<ReviewCommentStatus>>>()?;
+            list(&store_path, &store, task_id.as_deref(), &statuses);
+            Ok(())
+        }
+        "show" => {
+            let options = Options::parse(rest, &["task", "thread"])?;
+            let store = load_store(&worktree_root)?;
+            let task_id = require_task_id(options.get("task"), &worktree_root, &store)?;
+            let entry = store
+                .tasks
+                .get(&task_id)
+                .ok_or_else(|| message(format!("Review task entry not found: {task_id}")))?;
+            let index = thread_index(&options)?;
+            show(&worktree_root, select_thread(entry, index)?, index);
+            Ok(())
+        }
+        "add" => {
+            let options = Options::parse(
+                rest,
+                &[
+                    "task",
+                    "scope",
+                    "file",
+                    "line-old",
+                    "line-new",
+                    "range-start",
+                    "line-type",
+                    "status",
+                    "message",
+                    "suggestion",
+                    "author",
+                ],
+            )?;
+            add(&worktree_root, &store_path, &options)
+        }
+        "comment" | "resolve" => {
+            let options = Options::parse(rest, &["task", "thread", "message", "author"])?;
+            let index = thread_index(&options)?;
+            let comment = new_comment(options.message()?, options.get("author"));
+            let resolve = command == "resolve";
+            let (task_id, key) = update_thread(&worktree_root, &options, index, |thread| {
+                if resolve {
+                    thread.status = ReviewCommentStatus::Resolved;
+                }
+                thread.comments.push(comment);
+            })?;
+            println!(
+                "{command} OK task={task_id} thread=#{index} key={key} store={}",
+                store_path.display()
+            );
+            Ok(())
+        }
+        "set-status" => {
+            let options = Options::parse(rest, &["task", "thread", "status"])?;
+            let index = thread_index(&options)?;
+            options.required("status")?;
+            let status: ReviewCommentStatus = options.choice("status")?.unwrap_or_default();
+            let (task_id, key) = update_thread(&worktree_root, &options, index, |thread| {
+                thread.status = status;
+            })?;
+            println!(
+                "set-status OK task={task_id} thread=#{index} key={key} status={} store={}",
+                status_label(status),
+                store_path.display()
+            );
+            Ok(())
+        }
+        other => Err(message(format!(
+            "Unknown review command: {other}. Run `illuc-cli --help`."
+        ))),
+    }
+}
+
+fn infer_task_id(
+    explicit: Option<&str>,
+    worktree_root: &Path,
+    store: &ReviewStore,
+) -> Result<Option<String>> {
+    if let Some(task_id) = explicit.map(str::trim).filter(|value| !value.is_empty()) {
+        return Ok(Some(task_id.to_string()));
+    }
+    let folder = worktree_root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string());
+    if let Some(folder) = folder.filter(|name| Uuid::parse_str(name).is_ok()) {
+        return Ok(Some(folder));
+    }
+    let mut keys = store.tasks.keys();
+    match (keys.next(), keys.next()) {
+        (None, _) => Ok(None),
+        (Some(task_id), None) => Ok(Some(task_id.clone())),
+        _ => Err(message(format!(
+            "Unable to infer task id. Pass --task <uuid>. Found {} tasks in store.",
+            store.tasks.len()
+        ))),
+    }
+}
+
+fn require_task_id(
+    explicit: Option<&str>,
+    worktree_root: &Path,
+    store: &ReviewStore,
+) -> Result<String> {
+    infer_task_id(explicit, worktree_root, store)?
+        .ok_or_else(|| message("Unable to infer task id. Pass --task <uuid>.".to_string()))
+}
+
+fn thread_index(options: &Options) -> Result<usize> {
+    options
+        .required("thread")?
+        .trim()
+        .parse()
+        .map_err(|_| message("--thread must be a thread index from `list`".to_string()))
+}
+
+/// Thread `index` in `list` order.
+fn select_thread(entry: &TaskReviewEntry, index: usize) -> Result<&ReviewThread> {
+    sorted_threads(entry).get(index).copied().ok_or_else(|| {
+        message(format!(
+            "Thread index out of range: {index}. Use 'list' to see valid indices."
+        ))
+    })
+}
+
+/// Applies `apply` to thread `index` under the store lock; returns the task id and thread key.
+fn update_thread<F>(
+    worktree_root: &Path,
+    options: &Options,
+    index: usize,
+    apply: F,
+) -> Result<(String, String)>
+where
+    F: FnOnce(&mut ReviewThread),
+{
+    update_store(worktree_root, |store| {
+        let task_id = require_task_id(options.get("task"), worktree_root, store)?;
+        let entry = store
+            .tasks
+            .get_mut(&task_id)
+            .ok_or_else(|| message(format!("Review task entry not found: {task_id}")))?;
+        let thread_id = select_thread(entry, index)?.id.clone();
+        let thread = entry
+            .threads
+            .iter_mut()
+            .find(|thread| thread.id == thread_id)
+            .ok_or_else(|| message(format!("Review thread not found: {thread_id}")))?;
+        apply(thread);
+        Ok((task_id, thread_key(thread)))
+    })
+}
+
+fn add(worktree_root: &Path, store_path: &Path, options: &Options) -> Result<()> {
+    let scope: ReviewScope = options.choice("scope")?.unwrap_or_default();
+    let file_path = match scope {
+        ReviewScope::Task => String::new(),
+        _ => options.get("file").unwrap_or_default().trim().to_string(),
+    };
+    if scope != ReviewScope::Task && file_path.is_empty() {
+        return Err(message(
+            "--file is required unless --scope task".to_string(),
+        ));
+    }
+    let (line_old, line_new) = match scope {
+        ReviewScope::Line => (options.number("line-old")?, options.number("line-new")?),
+        _ => (None, None),
+    };
+    let mut range = ReviewRange::single(line_old, line_new);
+    if scope == ReviewScope::Line && range.is_none() {
+        return Err(message(
+            "Review thread must include --line-new or --line-old.".to_string(),
+        ));
+    }
+    if let (Some(start), Some(range)) = (options.number("range-start")?, range.as_mut()) {
+        if start < 1 || start > range.end {
+            return Err(message(
+                "--range-start must be between 1 and the thread's line.".to_string(),
+            ));
+        }
+        range.start = start;
+    }
+    let line_type: ReviewLineType = options.choice("line-type")?.unwrap_or_default();
+    let status: ReviewCommentStatus = options.choice("status")?.unwrap_or_default();
+    let mut comment = new_comment(options.message()?, options.get("author"));
+    if let Some(replacement) = options.get("suggestion") {
+        if range.map(|range| range.side) != Some(ReviewSide::New) {
+            return Err(message(
+                "--suggestion needs a line thread on the new side (--line-new).".to_string(),
+            ));
+        }
+        comment.suggestion = Some(ReviewSuggestion {
+            replacement: replacement.to_string(),
+            applied_at: None,
+        });
+    }
+    let anchor = range.and_then(|range| anchor_from_file(worktree_root, &file_path, range));
+
+    let (task_id, key) = update_store(worktree_root, |store| {
+        let task_id = require_task_id(options.get("task"), worktree_root, store)?;
+        let entry = store
+            .tasks
+            .entry(task_id.clone())
+            .or_insert_with(|| TaskReviewEntry::new(task_id.clone()));
+        // File- and task-level remarks always start a new thread; reply with `comment`.
+        let existing = entry.threads.iter().position(|thread| {
+            scope == ReviewScope::Line
+                && thread.scope == ReviewScope::Line
+                && thread.file_path == file_path
+                && thread.line_number_old == line_old
+                && thread.line_number_new == line_new
+                && thread.range == range
+        });
+        let index = existing.unwrap_or_else(|| {
+            entry.threads.push(ReviewThread {
+                id: Uuid::new_v4().to_string(),
+                scope,
+                file_path: file_path.clone(),
+                line_number_old: line_old,
+                line_number_new: line_new,
+                line_type,
+                range,
+                status,
+                comments: Vec::new(),
+                anchor,
+                outdated: false,
+            });
+            entry.threads.len() - 1
+        });
+        let thread = &mut entry.threads[index];
+        thread.comments.push(comment);
+        Ok((task_id, thread_key(thread)))
+    })?;
+    println!(
+        "add OK task={task_id} key={key} store={}",
+        store_path.display()
+    );
+    Ok(())
+}
+
+/// Comments written through the CLI come from an agent. illuc sets `ILLUC_AGENT_KIND` in the
+/// agent's environment; otherwise the author name may name the agent.
+fn new_comment(body: String, explicit_author: Option<&str>) -> ReviewComment {
+    let author = explicit_author
+        .map(str::to_string)
+        .or_else(|| std::env::var(AUTHOR_ENV).ok())
+        .map(|author| author.trim().to_string())
+        .filter(|author| !author.is_empty());
+    let parse_kind = |value: &str| -> Option<AgentKind> {
+        serde_json::from_value(Value::String(value.trim().to_lowercase())).ok()
+    };
+    let agent_kind = std::env::var(AGENT_KIND_ENV)
+        .ok()
+        .and_then(|value| parse_kind(&value))
+        .or_else(|| author.as_deref().and_then(parse_kind));
+    let author = author
+        .or_else(|| agent_kind.map(|kind| kind_label(kind).to_string()))
+        .unwrap_or_else(|| "agent".to_string());
+    ReviewComment {
+        id: Uuid::new_v4().to_string(),
+        body,
+        author,
+        author_email: None,
+        author_kind: ReviewAuthorKind::Agent,
+        agent_kind,
+        created_at: Utc::now(),
+        suggestion: None,
+    }
+}
+
+fn kind_label(kind: AgentKind) -> &'static str {
+    match kind {
+        AgentKind::Codex => "codex",
+        AgentKind::Copilot => "copilot",
+    }
+}
+
+fn status_label(status: ReviewCommentStatus) -> &'static str {
+    match status {
+        ReviewCommentStatus::Active => "active",
+        ReviewCommentStatus::Pending => "pending",
+        ReviewCommentStatus::Resolved => "resolved",
+        ReviewCommentStatus::WontFix => "wont-fix",
+        ReviewCommentStatus::Closed => "closed",
+    }
+}
+
+fn line_type_label(line_type: ReviewLineType) -> &'static str {
+    match line_type {
+        ReviewLineType::Add => "add",
+        ReviewLineType::Del => "del",
+        ReviewLineType::Context => "context",
+        ReviewLineType::Meta => "meta",
+        ReviewLineType::Hunk => "hunk",
+    }
+}
+
+fn thread_key(thread: &ReviewThread) -> String {
+    match thread.scope {
+        ReviewScope::Task => "task".to_string(),
+        ReviewScope::File => format!("{}::file", thread.file_path),
+        ReviewScope::Line => super::thread_key(
+            &thread.file_path,
+            thread.line_number_old,
+            thread.line_number_new,
+        ),
+    }
+}
+
+fn format_range(thread: &ReviewThread) -> String {
+    let Some(range) = thread.range else {
+        return "-".to_string();
+    };
+    let side = match range.side {
+        ReviewSide::Old => "old",
+        ReviewSide::New => "new",
+    };
+    if range.is_multi_line() {
+        format!("{side}:{}-{}", range.start, range.end)
+    } else {
+        format!("{side}:{}", range.start)
+    }
+}
+
+fn optional_line(line: Option<u32>) -> String {
+    line.map(|line| line.to_string())
+        .unwrap_or_else(|| "x".to_string())
+}
+
+fn open_suggestions(thread: &ReviewThread) -> usize {
+    thread
+        .comments
+        .iter()
+        .filter_map(|comment| comment.suggestion.as_ref())
+        .filter(|suggestion| suggestion.applied_at.is_none())
+        .count()
+}
+
+fn latest_preview(thread: &ReviewThread) -> Option<String> {
+    let body = thread.comments.last()?.body.lines().next()?;
+    if body.trim().is_empty() {
+        return None;
+    }
+    Some(body.chars().take(120).collect::<String>().replace('"', "'"))
+}
+
+fn list(
+    store_path: &Path,
+    store: &ReviewStore,
+    task_id: Option<&str>,
+    statuses: &[ReviewCommentStatus],
+) {
+    let Some(task_id) = task_id else {
+        println!("No review tasks found in {}", store_path.display());
+        return;
+    };
+    let Some(entry) = store.tasks.get(task_id) else {
+        println!(
+            "No review entry for task {task_id} in {}",
+            store_path.display()
+        );
+        return;
+    };
+    // Indices stay stable across filters: they come from the full sorted list.
+    let visible: Vec<(usize, &ReviewThread)> = sorted_threads(entry)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, thread)| statuses.is_empty() || statuses.contains(&thread.status))
+        .collect();
+    if visible.is_empty() {
+        let labels: Vec<&str> = statuses
+            .iter()
+            .map(|status| status_label(*status))
+            .collect();
+        println!("No threads matching status=[{}]", labels.join(", "));
+        return;
+    }
+    for (index, thread) in visible {
+        let mut parts = vec![
+            format!("#{index}"),
+            format!("status={}", status_label(thread.status)),
+        ];
+        match thread.scope {
+            ReviewScope::Line => parts.extend([
+                format!("file={}", thread.file_path),
+                format!("old={}", optional_line(thread.line_number_old)),
+                format!("new={}", optional_line(thread.line_number_new)),
+                format!("type={}", line_type_label(thread.line_type)),
+                format!("range={}", format_range(thread)),
+            ]),
+            ReviewScope::File => parts.extend([
+                "scope=file".to_string(),
+                format!("file={}", thread.file_path),
+            ]),
+            ReviewScope::Task => parts.push("scope=task".to_string()),
+        }
+        if thread.outdated {
+            parts.push("outdated".to_string());
+        }
+        parts.push(format!("comments={}", thread.comments.len()));
+        let suggestions = open_suggestions(thread);
+        if suggestions > 0 {
+            parts.push(format!("suggestions={suggestions}"));
+        }
+        if let Some(preview) = latest_preview(thread) {
+            parts.push(format!("latest=\"{preview}\""));
+        }
+        println!("{}", parts.join(" "));
+    }
+}
+
+fn read_lines(worktree_root: &Path, file_path: &str) -> Option<Vec<String>> {
+    let contents = std::fs::read_to_string(worktree_root.join(file_path)).ok()?;
+    Some(contents.lines().map(str::to_string).collect())
+}
+
+fn show(worktree_root: &Path, thread: &ReviewThread, index: usize) {
+    let target = match thread.scope {
+        ReviewScope::Task => "scope=task".to_string(),
+        ReviewScope::File => format!("scope=file file={}", thread.file_path),
+        ReviewScope::Line => format!("file={} range={}", thread.file_path, format_range(thread)),
+    };
+    let outdated = if thread.outdated { " outdated" } else { "" };
+    println!(
+        "#{index} status={} {target}{outdated}",
+        status_label(thread.status)
+    );
+    println!();
+    let lines = thread
+        .range
+        .filter(|range| range.side == ReviewSide::New && !thread.outdated)
+        .and_then(|range| Some((range, read_lines(worktree_root, &thread.file_path)?)))
+        .filter(|(range, lines)| {
+            range.start > 0 && range.start <= range.end && range.end as usize <= lines.len()
+        });
+    if let Some((range, lines)) = lines {
+        // Current worktree content of the range, with a little context around it.
+        let (start, end) = (range.start as usize, range.end as usize);
+        let first = start.saturating_sub(ANCHOR_CONTEXT_LINES).max(1);
+        let last = (end + ANCHOR_CONTEXT_LINES).min(lines.len());
+        for number in first..=last {
+            let marker = if (start..=end).contains(&number) {
+                ">"
+            } else {
+                " "
+            };
+            println!("{marker} {number:>5} | {}", lines[number - 1]);
+        }
+    } else if let Some(anchor) = &thread.anchor {
+        // Deleted or outdated code: show what the comment was originally left on.
+        println!("(code as it was when the comment was left)");
+        for line in &anchor.context_before {
+            println!("  {line}");
+        }
+        if anchor.lines.is_empty() {
+            println!("> {}", anchor.line);
+        }
+        for line in &anchor.lines {
+            println!("> {line}");
+        }
+        for line in &anchor.context_after {
+            println!("  {line}");
+        }
+    } else {
+        match thread.scope {
+            ReviewScope::Task => println!("(applies to the task as a whole)"),
+            ReviewScope::File => println!("(applies to the whole of {})", thread.file_path),
+            ReviewScope::Line => println!("(code for this thread is not available)"),
+        }
+    }
+    println!();
+    for comment in &thread.comments {
+        println!(
+            "--- {} at {}",
+            comment.author,
+            comment.created_at.to_rfc3339()
+        );
+        println!("{}", comment.body.trim_end());
+        if let Some(suggestion) = &comment.suggestion {
+            let state = if suggestion.applied_at.is_some() {
+                "applied"
+            } else {
+                "not applied"
+            };
+            println!(
+                "suggested replacement for lines {} ({state}):",
+                format_range(thread)
+            );
+            let mut replacement = suggestion.replacement.lines().peekable();
+            if replacement.peek().is_none() {
+                println!("+ ");
+            }
+            for line in replacement {
+                println!("+ {line}");
+            }
+        }
+    }
+}
@@ -1,6 +1,11 @@
 pub mod task_review_add_comment;
+pub mod task_review_apply_suggestion;
 pub mod task_review_delete_comment;
 pub mod task_review_edit_comment;
+pub mod task_review_export;
 pub mod task_review_get;
 pub mod task_review_get_user_display_name;
+pub mod task_review_set_file_viewed;
+pub mod task_review_set_verdict;
+pub mod task_review_submit;
 pub mod task_review_update_thread_status;
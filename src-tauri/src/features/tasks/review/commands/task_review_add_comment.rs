@@ -1,7 +1,11 @@
 use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::review::anchor::anchor_from_file;
+use crate::features::tasks::review::identity::resolve_identity;
+use crate::features::tasks::review::suggestion::accepts_suggestion;
 use crate::features::tasks::review::{
-    find_thread_mut, load_store, save_store, ReviewComment, ReviewLineType, ReviewThread,
-    TaskReviewEntry,
+    update_store, ReviewAuthorKind, ReviewComment, ReviewLineType, ReviewRange, ReviewScope,
+    ReviewSide, ReviewSuggestion, ReviewThread, TaskReviewEntry,
 };
 use chrono::Utc;
 use serde::Deserialize;
@@ -13,67 +17,138 @@ use uuid::Uuid;
 pub struct Request {
     pub worktree_path: String,
     pub task_id: String,
+    /// Replies to an existing thread; the target fields below are ignored.
+    pub thread_id: Option<String>,
+    /// Scope of a new thread; defaults to a line thread.
+    pub scope: Option<ReviewScope>,
+    #[serde(default)]
     pub file_path: String,
     pub line_number_old: Option<u32>,
     pub line_number_new: Option<u32>,
+    #[serde(default)]
     pub line_type: ReviewLineType,
+    /// Lines covered by a new line thread; defaults to the single line above.
+    pub range: Option<ReviewRange>,
     pub body: String,
+    /// Replacement text for the thread's lines; only for line threads on the new side.
+    pub suggestion: Option<String>,
+}
+
+fn new_thread(scope: ReviewScope, file_path: String) -> ReviewThread {
+    ReviewThread {
+        id: Uuid::new_v4().to_string(),
+        scope,
+        file_path,
+        line_number_old: None,
+        line_number_new: None,
+        line_type: ReviewLineType::default(),
+        range: None,
+        status: Default::default(),
+        comments: Vec::new(),
+        anchor: None,
+        outdated: false,
+    }
 }
 
 #[tauri::command]
-pub async fn task_review_add_comment(req: Request) -> CommandResult<ReviewComment> {
+pub async fn task_review_add_comment(
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<ReviewComment> {
     let body = req.body.trim().to_string();
     if body.is_empty() {
         return Err("Review comment body cannot be empty.".to_string());
     }
-    if req.file_path.trim().is_empty() || req.task_id.trim().is_empty() {
+    let scope = req.scope.unwrap_or_default();
+    let needs_file = req.thread_id.is_none() && scope != ReviewScope::Task;
+    if req.task_id.trim().is_empty() || (needs_file && req.file_path.trim().is_empty()) {
         return Err("Review comment target is invalid.".to_string());
     }
-    if req.line_number_old.is_none() && req.line_number_new.is_none() {
-        return Err("Review comment must include a line number.".to_string());
-    }
+    let range = if req.thread_id.is_none() && scope == ReviewScope::Line {
+        let range = match req.range {
+            Some(range) => range,
+            None => ReviewRange::single(req.line_number_old, req.line_number_new)
+                .ok_or_else(|| "Review comment must include a line number.".to_string())?,
+        };
+        let end_line = match range.side {
+            ReviewSide::Old => req.line_number_old,
+            ReviewSide::New => req.line_number_new,
+        };
+        if range.start == 0 || range.start > range.end || end_line != Some(range.end) {
+            return Err("Review comment range is invalid.".to_string());
+        }
+        Some(range)
+    } else {
+        None
+    };
     let worktree_path = req.worktree_path.clone();
     let worktree_root = PathBuf::from(&worktree_path);
-    let mut store = load_store(&worktree_root).map_err(|err| err.to_string())?;
-    let entry = store
-        .tasks
-        .entry(req.task_id.clone())
-        .or_insert_with(|| TaskReviewEntry {
-            task_id: req.task_id.clone(),
-            threads: Vec::new(),
-        });
+    let identity = resolve_identity(&app_handle, Some(&worktree_root));
+    update_store(&worktree_root, |store| {
+        let entry = store
+            .tasks
+            .entry(req.task_id.clone())
+            .or_insert_with(|| TaskReviewEntry::new(req.task_id.clone()));
 
-    if find_thread_mut(
-        entry,
-        &req.file_path,
-        req.line_number_old,
-        req.line_number_new,
-    )
-    .is_none()
-    {
-        entry.threads.push(ReviewThread {
-            file_path: req.file_path.clone(),
-            line_number_old: req.line_number_old,
-            line_number_new: req.line_number_new,
-            line_type: req.line_type,
-            status: Default::default(),
-            comments: Vec::new(),
-        });
-    }
-    let thread = find_thread_mut(
-        entry,
-        &req.file_path,
-        req.line_number_old,
-        req.line_number_new,
-    )
-    .ok_or_else(|| "Review thread target is invalid.".to_string())?;
-    let comment = ReviewComment {
-        id: Uuid::new_v4().to_string(),
-        body,
-        author: "user".to_string(),
-        created_at: Utc::now(),
-    };
-    thread.comments.push(comment.clone());
-    save_store(&worktree_root, &store).map_err(|err| err.to_string())?;
-    Ok(comment)
+        let thread = match (&req.thread_id, range) {
+            (Some(thread_id), _) => entry
+                .threads
+                .iter_mut()
+                .find(|thread| &thread.id == thread_id)
+                .ok_or_else(|| TaskError::Message("Review thread not found.".to_string()))?,
+            (None, Some(range)) => {
+                // A comment on the same lines joins their thread; a different span starts its own.
+                let existing = entry.threads.iter().position(|thread| {
+                    thread.scope == ReviewScope::Line
+                        && thread.file_path == req.file_path
+                        && thread.line_number_old == req.line_number_old
+                        && thread.line_number_new == req.line_number_new
+                        && thread.range == Some(range)
+                });
+                let index = existing.unwrap_or_else(|| {
+                    entry.threads.push(ReviewThread {
+                        line_number_old: req.line_number_old,
+                        line_number_new: req.line_number_new,
+                        line_type: req.line_type,
+                        range: Some(range),
+                        anchor: anchor_from_file(&worktree_root, &req.file_path, range),
+                        ..new_thread(ReviewScope::Line, req.file_path.clone())
+                    });
+                    entry.threads.len() - 1
+                });
+                &mut entry.threads[index]
+            }
+            (None, None) => {
+                let file_path = match scope {
+                    ReviewScope::Task => String::new(),
+                    _ => req.file_path.clone(),
+                };
+                entry.threads.push(new_thread(scope, file_path));
+                entry.threads.last_mut().ok_or_else(|| {
+                    TaskError::Message("Review thread target is invalid.".to_string())
+                })?
+            }
+        };
+        if req.suggestion.is_some() && !accepts_suggestion(thread) {
+            return Err(TaskError::Message(
+                "Suggestions can only be added to worktree lines.".to_string(),
+            ));
+        }
+        let comment = ReviewComment {
+            id: Uuid::new_v4().to_string(),
+            body,
+            author: identity.name,
+            author_email: identity.email,
+            author_kind: ReviewAuthorKind::User,
+            agent_kind: None,
+            created_at: Utc::now(),
+            suggestion: req.suggestion.clone().map(|replacement| ReviewSuggestion {
+                replacement,
+                applied_at: None,
+            }),
+        };
+        thread.comments.push(comment.clone());
+        Ok(comment)
+    })
+    .map_err(|err| err.to_string())
 }
@@ -0,0 +1,41 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::review::suggestion::apply_suggestion;
+use crate::features::tasks::review::{update_store, ReviewThread};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub worktree_path: String,
+    pub task_id: String,
+    pub thread_id: String,
+    pub comment_id: String,
+}
+
+#[tauri::command]
+pub async fn task_review_apply_suggestion(req: Request) -> CommandResult<ReviewThread> {
+    if req.task_id.trim().is_empty()
+        || req.thread_id.trim().is_empty()
+        || req.comment_id.trim().is_empty()
+    {
+        return Err("Review suggestion target is invalid.".to_string());
+    }
+    let worktree_root = PathBuf::from(&req.worktree_path);
+    update_store(&worktree_root, |store| {
+        let thread = store
+            .tasks
+            .get_mut(&req.task_id)
+            .and_then(|entry| {
+                entry
+                    .threads
+                    .iter_mut()
+                    .find(|thread| thread.id == req.thread_id)
+            })
+            .ok_or_else(|| TaskError::Message("Review thread not found.".to_string()))?;
+        apply_suggestion(&worktree_root, thread, &req.comment_id)?;
+        Ok(thread.clone())
+    })
+    .map_err(|err| err.to_string())
+}
@@ -1,5 +1,9 @@
 use crate::commands::CommandResult;
-use crate::features::tasks::review::{find_thread_mut, load_store, save_store, ReviewComment};
+use crate::error::TaskError;
+use crate::features::tasks::review::suggestion::accepts_suggestion;
+use crate::features::tasks::review::{
+    locate_thread_mut, update_store, ReviewComment, ReviewSuggestion,
+};
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -8,23 +12,29 @@ use std::path::PathBuf;
 pub struct Request {
     pub worktree_path: String,
     pub task_id: String,
+    /// Targets the thread by id instead of by position; required for file and task threads.
+    pub thread_id: Option<String>,
+    #[serde(default)]
     pub file_path: String,
     pub line_number_old: Option<u32>,
     pub line_number_new: Option<u32>,
     pub comment_id: String,
     pub body: String,
+    /// Replaces the comment's suggestion when given; applied suggestions cannot change.
+    pub suggestion: Option<String>,
 }
 
 #[tauri::command]
 pub async fn task_review_edit_comment(req: Request) -> CommandResult<ReviewComment> {
     let body = req.body.trim().to_string();
+    let by_position = req.thread_id.is_none();
     if req.task_id.trim().is_empty()
-        || req.file_path.trim().is_empty()
+        || (by_position && req.file_path.trim().is_empty())
         || req.comment_id.trim().is_empty()
     {
         return Err("Review comment target is invalid.".to_string());
     }
-    if req.line_number_old.is_none() && req.line_number_new.is_none() {
+    if by_position && req.line_number_old.is_none() && req.line_number_new.is_none() {
         return Err("Review comment must include a line number.".to_string());
     }
     if body.is_empty() {
@@ -32,25 +42,46 @@ pub async fn task_review_edit_comment(req: Request) -> CommandResult<ReviewComme
     }
 
     let worktree_root = PathBuf::from(&req.worktree_path);
-    let mut store = load_store(&worktree_root).map_err(|err| err.to_string())?;
-    let entry = store
-        .tasks
-        .get_mut(&req.task_id)
-        .ok_or_else(|| "Review task entry not found.".to_string())?;
-    let thread = find_thread_mut(
-        entry,
-        &req.file_path,
-        req.line_number_old,
-        req.line_number_new,
-    )
-    .ok_or_else(|| "Review thread not found.".to_string())?;
-    let comment = thread
-        .comments
-        .iter_mut()
-        .find(|comment| comment.id == req.comment_id)
-        .ok_or_else(|| "Review comment not found.".to_string())?;
-    comment.body = body;
-    let updated = comment.clone();
-    save_store(&worktree_root, &store).map_err(|err| err.to_string())?;
-    Ok(updated)
+    update_store(&worktree_root, |store| {
+        let entry = store
+            .tasks
+            .get_mut(&req.task_id)
+            .ok_or_else(|| TaskError::Message("Review task entry not found.".to_string()))?;
+        let thread = locate_thread_mut(
+            entry,
+            req.thread_id.as_deref(),
+            &req.file_path,
+            req.line_number_old,
+            req.line_number_new,
+        )
+        .ok_or_else(|| TaskError::Message("Review thread not found.".to_string()))?;
+        if req.suggestion.is_some() && !accepts_suggestion(thread) {
+            return Err(TaskError::Message(
+                "Suggestions can only be added to worktree lines.".to_string(),
+            ));
+        }
+        let comment = thread
+            .comments
+            .iter_mut()
+            .find(|comment| comment.id == req.comment_id)
+            .ok_or_else(|| TaskError::Message("Review comment not found.".to_string()))?;
+        if let Some(replacement) = req.suggestion.clone() {
+            if comment
+                .suggestion
+                .as_ref()
+                .is_some_and(|suggestion| suggestion.applied_at.is_some())
+            {
+                return Err(TaskError::Message(
+                    "The suggestion was already applied.".to_string(),
+                ));
+            }
+            comment.suggestion = Some(ReviewSuggestion {
+                replacement,
+                applied_at: None,
+            });
+        }
+        comment.body = body;
+        Ok(comment.clone())
+    })
+    .map_err(|err| err.to_string())
 }
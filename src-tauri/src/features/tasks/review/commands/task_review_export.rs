@@ -0,0 +1,74 @@
+use crate::commands::CommandResult;
+use crate::error::TaskError;
+use crate::features::tasks::git::{git_diff, DiffContext};
+use crate::features::tasks::review::export::{
+    render_html, render_markdown, ReviewExportContext, ReviewExportFormat,
+};
+use crate::features::tasks::review::load_store;
+use crate::features::tasks::TaskManager;
+use crate::utils::fs::{export_path, write_replacing};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    pub format: ReviewExportFormat,
+    /// When set the report is written there; otherwise it is only returned.
+    pub output_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub contents: String,
+    pub output_path: Option<String>,
+}
+
+/// Renders the task's review threads and branch diff stats as a standalone report.
+#[tauri::command]
+pub async fn task_review_export(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let summary = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&req.task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        record.summary.clone()
+    };
+    let worktree_root = PathBuf::from(&summary.worktree_path);
+    let base_commit = summary.base_commit.trim();
+    let files = git_diff(
+        worktree_root.as_path(),
+        base_commit,
+        None,
+        &[],
+        DiffContext::default(),
+    )
+    .map_err(|err| err.to_string())?
+    .files;
+    let store = load_store(&worktree_root).map_err(|err| err.to_string())?;
+    let entry = store.tasks.get(&req.task_id.to_string());
+    let context = ReviewExportContext {
+        title: &summary.title,
+        branch_name: &summary.branch_name,
+        base_branch: &summary.base_branch,
+        base_commit,
+    };
+    let contents = match req.format {
+        ReviewExportFormat::Markdown => render_markdown(&context, entry, &files),
+        ReviewExportFormat::Html => render_html(&context, entry, &files),
+    };
+    if let Some(output_path) = &req.output_path {
+        let output_path = export_path(output_path).map_err(|err| err.to_string())?;
+        write_replacing(&output_path, contents.as_bytes()).map_err(|err| err.to_string())?;
+    }
+    Ok(Response {
+        contents,
+        output_path: req.output_path,
+    })
+}
@@ -1,5 +1,5 @@
 use crate::commands::CommandResult;
-use crate::features::tasks::review::{load_store, ReviewStore};
+use crate::features::tasks::review::{load_store, ReviewStoreView};
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -9,10 +9,12 @@ pub struct Request {
     pub worktree_path: String,
 }
 
-pub type Response = ReviewStore;
+pub type Response = ReviewStoreView;
 
 #[tauri::command]
 pub async fn task_review_get(req: Request) -> CommandResult<Response> {
     let worktree_root = PathBuf::from(req.worktree_path);
-    load_store(&worktree_root).map_err(|err| err.to_string())
+    load_store(&worktree_root)
+        .map(ReviewStoreView::from)
+        .map_err(|err| err.to_string())
 }
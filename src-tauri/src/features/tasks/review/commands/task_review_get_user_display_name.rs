@@ -1,11 +1,9 @@
 use crate::commands::CommandResult;
+use crate::features::tasks::review::identity::resolve_identity;
 
 #[tauri::command]
-pub async fn task_review_get_user_display_name() -> CommandResult<String> {
-    let realname = whoami::realname();
-    let trimmed = realname.trim();
-    if !trimmed.is_empty() {
-        return Ok(trimmed.to_string());
-    }
-    Ok(whoami::username())
+pub async fn task_review_get_user_display_name(
+    app_handle: tauri::AppHandle,
+) -> CommandResult<String> {
+    Ok(resolve_identity(&app_handle, None).name)
 }
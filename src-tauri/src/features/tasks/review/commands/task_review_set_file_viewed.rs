@@ -0,0 +1,54 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::git::worktree_blob_id;
+use crate::features::tasks::review::{update_store, TaskReviewEntry, ViewedFile};
+use chrono::Utc;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub worktree_path: String,
+    pub task_id: String,
+    pub file_path: String,
+    pub viewed: bool,
+}
+
+/// The stored mark; `None` once the file is no longer marked viewed.
+pub type Response = Option<ViewedFile>;
+
+#[tauri::command]
+pub async fn task_review_set_file_viewed(req: Request) -> CommandResult<Response> {
+    if req.task_id.trim().is_empty() || req.file_path.trim().is_empty() {
+        return Err("Viewed file target is invalid.".to_string());
+    }
+    let worktree_root = PathBuf::from(&req.worktree_path);
+    let blob_id = if req.viewed {
+        Some(worktree_blob_id(&worktree_root, &req.file_path).map_err(|err| err.to_string())?)
+    } else {
+        None
+    };
+    update_store(&worktree_root, |store| {
+        let entry = store
+            .tasks
+            .entry(req.task_id.clone())
+            .or_insert_with(|| TaskReviewEntry::new(req.task_id.clone()));
+        match blob_id {
+            Some(blob_id) => {
+                let mark = ViewedFile {
+                    blob_id,
+                    viewed_at: Utc::now(),
+                };
+                entry
+                    .viewed_files
+                    .insert(req.file_path.clone(), mark.clone());
+                Ok(Some(mark))
+            }
+            None => {
+                entry.viewed_files.remove(&req.file_path);
+                Ok(None)
+            }
+        }
+    })
+    .map_err(|err| err.to_string())
+}
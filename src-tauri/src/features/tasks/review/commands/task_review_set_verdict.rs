@@ -0,0 +1,52 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::review::identity::resolve_identity;
+use crate::features::tasks::review::verdict::{set_verdict, TaskVerdict};
+use crate::features::tasks::review::{
+    update_store, ReviewVerdict, TaskReviewEntry, TaskReviewView,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub worktree_path: String,
+    pub task_id: String,
+    pub verdict: ReviewVerdict,
+}
+
+pub type Response = TaskReviewView;
+
+#[tauri::command]
+pub async fn task_review_set_verdict(
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<Response> {
+    if req.task_id.trim().is_empty() {
+        return Err("Review task id is required.".to_string());
+    }
+    let worktree_root = PathBuf::from(&req.worktree_path);
+    let identity = resolve_identity(&app_handle, Some(&worktree_root));
+    let verdict = if req.verdict == ReviewVerdict::Unreviewed {
+        TaskVerdict::default()
+    } else {
+        TaskVerdict {
+            verdict: req.verdict,
+            verdict_by: Some(identity.name),
+            verdict_at: Some(Utc::now()),
+        }
+    };
+    set_verdict(&worktree_root, &req.task_id, verdict.clone()).map_err(|err| err.to_string())?;
+    update_store(&worktree_root, |store| {
+        let entry = store
+            .tasks
+            .entry(req.task_id.clone())
+            .or_insert_with(|| TaskReviewEntry::new(req.task_id.clone()));
+        entry.verdict = verdict.verdict;
+        entry.verdict_by = verdict.verdict_by;
+        entry.verdict_at = verdict.verdict_at;
+        Ok(TaskReviewView::from(entry.clone()))
+    })
+    .map_err(|err| err.to_string())
+}
@@ -0,0 +1,132 @@
+use crate::commands::CommandResult;
+use crate::error::{Result, TaskError};
+use crate::features::tasks::events::emit_review_changed;
+use crate::features::tasks::review::submit::build_prompt;
+use crate::features::tasks::review::{load_store, update_store, ReviewCommentStatus};
+use crate::features::tasks::{TaskManager, TaskStatus};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub task_id: Uuid,
+    /// Terminal size used when the agent has to be started.
+    pub cols: Option<u16>,
+    pub rows: Option<u16>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    /// Threads handed to the agent. They turn pending once the prompt reached it, which is
+    /// after this returns when the agent had to be started.
+    pub thread_ids: Vec<String>,
+    pub started_agent: bool,
+}
+
+/// Sends the task's active review threads to its agent and marks them pending once sent.
+#[tauri::command]
+pub async fn task_review_submit(
+    manager: tauri::State<'_, TaskManager>,
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<Response> {
+    let task_id = req.task_id;
+    let worktree_root = manager
+        .worktree_path(task_id)
+        .map_err(|err| err.to_string())?;
+    let task_key = task_id.to_string();
+    let store = load_store(&worktree_root).map_err(|err| err.to_string())?;
+    let threads: Vec<_> = store
+        .tasks
+        .get(&task_key)
+        .map(|entry| {
+            entry
+                .threads
+                .iter()
+                .filter(|thread| thread.status == ReviewCommentStatus::Active)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    if threads.is_empty() {
+        return Err("There are no active review threads to submit.".to_string());
+    }
+    let prompt = build_prompt(&threads);
+
+    let running = {
+        let tasks = manager.inner.tasks.read();
+        let record = tasks
+            .get(&task_id)
+            .ok_or_else(|| TaskError::NotFound.to_string())?;
+        if record.review_pass.is_some() {
+            return Err(
+                "A review pass is running on this task; submit once it has finished.".to_string(),
+            );
+        }
+        let running = record.runtime.is_some();
+        // Pasted into a busy agent, the prompt would answer an approval or land mid-turn.
+        if running
+            && matches!(
+                record.summary.status,
+                TaskStatus::Working | TaskStatus::AwaitingApproval
+            )
+        {
+            return Err(
+                "The agent is busy on this task; submit once it is waiting for input.".to_string(),
+            );
+        }
+        running
+    };
+    let thread_ids: Vec<String> = threads.into_iter().map(|thread| thread.id).collect();
+    if running {
+        let sender = manager.inner().clone();
+        tauri::async_runtime::spawn_blocking(move || sender.send_agent_prompt(task_id, &prompt))
+            .await
+            .map_err(|err| err.to_string())?
+            .map_err(|err| err.to_string())?;
+        mark_pending(&worktree_root, &task_key, &thread_ids).map_err(|err| err.to_string())?;
+    } else {
+        let delivery = manager
+            .start_agent_with_prompt(task_id, None, req.rows, req.cols, prompt, &app_handle)
+            .map_err(|err| err.to_string())?;
+        let thread_ids = thread_ids.clone();
+        std::thread::spawn(move || {
+            if !matches!(delivery.join(), Ok(Ok(()))) {
+                warn!(
+                    "review threads of task {} stay active; the prompt was not sent",
+                    task_id
+                );
+                return;
+            }
+            match mark_pending(&worktree_root, &task_key, &thread_ids) {
+                Ok(()) => emit_review_changed(&app_handle, task_id),
+                Err(err) => warn!(
+                    "failed to mark review threads of task {} pending: {}",
+                    task_id, err
+                ),
+            }
+        });
+    }
+
+    Ok(Response {
+        thread_ids,
+        started_agent: !running,
+    })
+}
+
+fn mark_pending(worktree_root: &Path, task_key: &str, thread_ids: &[String]) -> Result<()> {
+    update_store(worktree_root, |store| {
+        if let Some(entry) = store.tasks.get_mut(task_key) {
+            for thread in &mut entry.threads {
+                if thread_ids.contains(&thread.id) && thread.status == ReviewCommentStatus::Active {
+                    thread.status = ReviewCommentStatus::Pending;
+                }
+            }
+        }
+        Ok(())
+    })
+}
@@ -1,6 +1,7 @@
 use crate::commands::CommandResult;
+use crate::error::TaskError;
 use crate::features::tasks::review::{
-    find_thread_mut, load_store, save_store, thread_key, ReviewCommentStatus,
+    locate_thread_mut, thread_key, update_store, ReviewCommentStatus,
 };
 use serde::Deserialize;
 use std::path::PathBuf;
@@ -10,6 +11,9 @@ use std::path::PathBuf;
 pub struct Request {
     pub worktree_path: String,
     pub task_id: String,
+    /// Targets the thread by id instead of by position; required for file and task threads.
+    pub thread_id: Option<String>,
+    #[serde(default)]
     pub file_path: String,
     pub line_number_old: Option<u32>,
     pub line_number_new: Option<u32>,
@@ -25,28 +29,32 @@ pub struct Response {
 
 #[tauri::command]
 pub async fn task_review_update_thread_status(req: Request) -> CommandResult<Response> {
-    if req.task_id.trim().is_empty() || req.file_path.trim().is_empty() {
+    let by_position = req.thread_id.is_none();
+    if req.task_id.trim().is_empty() || (by_position && req.file_path.trim().is_empty()) {
         return Err("Review thread target is invalid.".to_string());
     }
-    if req.line_number_old.is_none() && req.line_number_new.is_none() {
+    if by_position && req.line_number_old.is_none() && req.line_number_new.is_none() {
         return Err("Review thread must include a line number.".to_string());
     }
     let worktree_root = PathBuf::from(&req.worktree_path);
-    let mut store = load_store(&worktree_root).map_err(|err| err.to_string())?;
-    let entry = store
-        .tasks
-        .get_mut(&req.task_id)
-        .ok_or_else(|| "Review task entry not found.".to_string())?;
     let key = thread_key(&req.file_path, req.line_number_old, req.line_number_new);
-    let thread = find_thread_mut(
-        entry,
-        &req.file_path,
-        req.line_number_old,
-        req.line_number_new,
-    )
-    .ok_or_else(|| "Review thread not found.".to_string())?;
-    thread.status = req.status;
-    save_store(&worktree_root, &store).map_err(|err| err.to_string())?;
+    update_store(&worktree_root, |store| {
+        let entry = store
+            .tasks
+            .get_mut(&req.task_id)
+            .ok_or_else(|| TaskError::Message("Review task entry not found.".to_string()))?;
+        let thread = locate_thread_mut(
+            entry,
+            req.thread_id.as_deref(),
+            &req.file_path,
+            req.line_number_old,
+            req.line_number_new,
+        )
+        .ok_or_else(|| TaskError::Message("Review thread not found.".to_string()))?;
+        thread.status = req.status;
+        Ok(())
+    })
+    .map_err(|err| err.to_string())?;
     Ok(Response {
         thread_key: key,
         status: req.status,
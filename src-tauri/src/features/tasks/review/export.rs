@@ -0,0 +1,462 @@
+use super::anchor::{line_number, side_lines};
+use super::{
+    sorted_threads, ReviewComment, ReviewCommentStatus, ReviewScope, ReviewSide, ReviewThread,
+    ReviewVerdict, TaskReviewEntry,
+};
+use crate::features::tasks::git::DiffFile;
+use crate::features::tasks::{agent_label, DiffLineType};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// Diff lines shown around a thread's range.
+const CODE_CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewExportFormat {
+    Markdown,
+    Html,
+}
+
+/// What the report is about; taken from the task rather than the review store.
+pub struct ReviewExportContext<'a> {
+    pub title: &'a str,
+    pub branch_name: &'a str,
+    pub base_branch: &'a str,
+    pub base_commit: &'a str,
+}
+
+struct FileStats {
+    path: String,
+    status: String,
+    additions: usize,
+    deletions: usize,
+}
+
+struct CodeLine {
+    marker: char,
+    number: Option<u32>,
+    content: String,
+    in_range: bool,
+}
+
+/// Code shown with a thread: the current diff when the range is still in it, otherwise the
+/// anchored content from when the thread was left.
+struct ThreadCode {
+    lines: Vec<CodeLine>,
+    from_anchor: bool,
+}
+
+fn file_stats(files: &[DiffFile]) -> Vec<FileStats> {
+    files
+        .iter()
+        .map(|file| FileStats {
+            path: file.path.clone(),
+            status: file.status.clone(),
+            additions: file
+                .lines
+                .iter()
+                .filter(|line| matches!(line.line_type, DiffLineType::Add))
+                .count(),
+            deletions: file
+                .lines
+                .iter()
+                .filter(|line| matches!(line.line_type, DiffLineType::Del))
+                .count(),
+        })
+        .collect()
+}
+
+fn thread_code(thread: &ReviewThread, files: &[DiffFile]) -> Option<ThreadCode> {
+    let range = thread.range?;
+    let lines = files
+        .iter()
+        .find(|file| file.path == thread.file_path)
+        .map(|file| side_lines(file, range.side))
+        .unwrap_or_default();
+    let in_range = |number: Option<u32>| {
+        number.is_some_and(|number| number >= range.start && number <= range.end)
+    };
+    let first = lines
+        .iter()
+        .position(|line| in_range(line_number(line, range.side)));
+    let last = lines
+        .iter()
+        .rposition(|line| in_range(line_number(line, range.side)));
+    if let (false, Some(first), Some(last)) = (thread.outdated, first, last) {
+        let start = first.saturating_sub(CODE_CONTEXT_LINES);
+        let end = (last + 1 + CODE_CONTEXT_LINES).min(lines.len());
+        let code = lines[start..end]
+            .iter()
+            .map(|line| {
+                let number = line_number(line, range.side);
+                CodeLine {
+                    marker: match line.line_type {
+                        DiffLineType::Add => '+',
+                        DiffLineType::Del => '-',
+                        _ => ' ',
+                    },
+                    number,
+                    content: line.content.clone(),
+                    in_range: in_range(number),
+                }
+            })
+            .collect();
+        return Some(ThreadCode {
+            lines: code,
+            from_anchor: false,
+        });
+    }
+
+    let anchor = thread.anchor.as_ref()?;
+    let anchored = if anchor.lines.is_empty() {
+        std::slice::from_ref(&anchor.line)
+    } else {
+        anchor.lines.as_slice()
+    };
+    let plain = |content: &String, in_range: bool| CodeLine {
+        marker: ' ',
+        number: None,
+        content: content.clone(),
+        in_range,
+    };
+    let code = anchor
+        .context_before
+        .iter()
+        .map(|line| plain(line, false))
+        .chain(anchored.iter().map(|line| plain(line, true)))
+        .chain(anchor.context_after.iter().map(|line| plain(line, false)))
+        .collect();
+    Some(ThreadCode {
+        lines: code,
+        from_anchor: true,
+    })
+}
+
+fn thread_location(thread: &ReviewThread) -> String {
+    match (thread.scope, thread.range) {
+        (ReviewScope::Task, _) => "Whole change".to_string(),
+        (ReviewScope::File, _) => format!("{} (file)", thread.file_path),
+        (ReviewScope::Line, Some(range)) => {
+            let side = match range.side {
+                ReviewSide::New => "",
+                ReviewSide::Old => " (removed)",
+            };
+            if range.is_multi_line() {
+                format!(
+                    "{} lines {}-{}{side}",
+                    thread.file_path, range.start, range.end
+                )
+            } else {
+                format!("{} line {}{side}", thread.file_path, range.end)
+            }
+        }
+        (ReviewScope::Line, None) => thread.file_path.clone(),
+    }
+}
+
+fn status_label(status: ReviewCommentStatus) -> &'static str {
+    match status {
+        ReviewCommentStatus::Active => "Active",
+        ReviewCommentStatus::Pending => "Pending",
+        ReviewCommentStatus::Resolved => "Resolved",
+        ReviewCommentStatus::WontFix => "Won't fix",
+        ReviewCommentStatus::Closed => "Closed",
+    }
+}
+
+fn verdict_label(verdict: ReviewVerdict) -> &'static str {
+    match verdict {
+        ReviewVerdict::Unreviewed => "Unreviewed",
+        ReviewVerdict::ChangesRequested => "Changes requested",
+        ReviewVerdict::Approved => "Approved",
+    }
+}
+
+fn timestamp(value: DateTime<Utc>) -> String {
+    value.format("%Y-%m-%d %H:%M UTC").to_string()
+}
+
+fn author_label(comment: &ReviewComment) -> String {
+    match comment.agent_kind.map(agent_label) {
+        Some(label) if !label.eq_ignore_ascii_case(&comment.author) => {
+            format!("{} ({label})", comment.author)
+        }
+        _ => comment.author.clone(),
+    }
+}
+
+fn verdict_line(entry: Option<&TaskReviewEntry>) -> String {
+    let Some(entry) = entry else {
+        return verdict_label(ReviewVerdict::Unreviewed).to_string();
+    };
+    let mut line = verdict_label(entry.verdict).to_string();
+    if let Some(by) = &entry.verdict_by {
+        line.push_str(&format!(" by {by}"));
+    }
+    if let Some(at) = entry.verdict_at {
+        line.push_str(&format!(" on {}", timestamp(at)));
+    }
+    line
+}
+
+fn thread_summary(entry: Option<&TaskReviewEntry>) -> String {
+    let Some(entry) = entry else {
+        return "no threads".to_string();
+    };
+    let resolved = entry
+        .threads
+        .iter()
+        .filter(|thread| thread.status == ReviewCommentStatus::Resolved)
+        .count();
+    format!(
+        "{} total, {} open, {} resolved",
+        entry.threads.len(),
+        entry.open_thread_count(),
+        resolved
+    )
+}
+
+/// A code fence longer than any backtick run in `text`.
+fn markdown_fence(text: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in text.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|")
+}
+
+fn code_line_text(line: &CodeLine) -> String {
+    let number = line
+        .number
+        .map(|number| number.to_string())
+        .unwrap_or_default();
+    let focus = if line.in_range { '>' } else { ' ' };
+    format!("{focus}{} {number:>5} | {}", line.marker, line.content)
+}
+
+pub fn render_markdown(
+    context: &ReviewExportContext<'_>,
+    entry: Option<&TaskReviewEntry>,
+    files: &[DiffFile],
+) -> String {
+    let stats = file_stats(files);
+    let mut out = format!("# Review: {}\n\n", context.title);
+    out.push_str(&format!(
+        "- Branch: `{}` (base `{}` at `{}`)\n",
+        context.branch_name,
+        context.base_branch,
+        short_commit(context.base_commit)
+    ));
+    out.push_str(&format!("- Verdict: {}\n", verdict_line(entry)));
+    out.push_str(&format!("- Threads: {}\n", thread_summary(entry)));
+    out.push_str(&format!("- Exported: {}\n\n", timestamp(Utc::now())));
+
+    out.push_str("## Changes\n\n");
+    let additions: usize = stats.iter().map(|file| file.additions).sum();
+    let deletions: usize = stats.iter().map(|file| file.deletions).sum();
+    out.push_str(&format!(
+        "{} file(s) changed, +{additions} -{deletions}\n\n",
+        stats.len()
+    ));
+    if !stats.is_empty() {
+        out.push_str("| File | Status | + | - |\n|---|---|---:|---:|\n");
+        for file in &stats {
+            out.push_str(&format!(
+                "| `{}` | {} | {} | {} |\n",
+                markdown_cell(&file.path),
+                file.status,
+                file.additions,
+                file.deletions
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Threads\n");
+    let threads = entry.map(sorted_threads).unwrap_or_default();
+    if threads.is_empty() {
+        out.push_str("\nNo review threads.\n");
+    }
+    for (index, thread) in threads.iter().enumerate() {
+        out.push_str(&format!(
+            "\n### {}. {} ({}{})\n\n",
+            index + 1,
+            thread_location(thread),
+            status_label(thread.status),
+            if thread.outdated { ", outdated" } else { "" }
+        ));
+        if let Some(code) = thread_code(thread, files) {
+            if code.from_anchor {
+                out.push_str("_Code as it was when the thread was started:_\n\n");
+            }
+            let text = code
+                .lines
+                .iter()
+                .map(code_line_text)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let fence = markdown_fence(&text);
+            out.push_str(&format!("{fence}\n{text}\n{fence}\n\n"));
+        }
+        for comment in &thread.comments {
+            out.push_str(&format!(
+                "**{}** · {}\n\n",
+                author_label(comment),
+                timestamp(comment.created_at)
+            ));
+            for line in comment.body.trim().lines() {
+                out.push_str(&format!("> {line}\n"));
+            }
+            out.push('\n');
+            if let Some(suggestion) = &comment.suggestion {
+                let state = if suggestion.applied_at.is_some() {
+                    "applied"
+                } else {
+                    "not applied"
+                };
+                let fence = markdown_fence(&suggestion.replacement);
+                out.push_str(&format!(
+                    "Suggested replacement ({state}):\n\n{fence}\n{}\n{fence}\n\n",
+                    suggestion.replacement.trim_end_matches(['\r', '\n'])
+                ));
+            }
+        }
+    }
+    out
+}
+
+fn short_commit(commit: &str) -> &str {
+    commit.get(..10).unwrap_or(commit)
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:960px;margin:2rem auto;\
+padding:0 1rem;color:#1f2328}code,pre{font-family:ui-monospace,monospace;font-size:12px}\
+table{border-collapse:collapse}td,th{border:1px solid #d0d7de;padding:2px 8px}\
+td.num{text-align:right}.thread{border:1px solid #d0d7de;border-radius:6px;margin:1rem 0;\
+padding:0 1rem 1rem}.status{font-size:12px;padding:1px 6px;border-radius:10px;\
+background:#eaeef2}pre.code{background:#f6f8fa;padding:8px;overflow-x:auto}\
+.focus{background:#fff8c5}.add{color:#1a7f37}.del{color:#cf222e}.meta{color:#57606a;\
+font-size:12px}.body{white-space:pre-wrap;margin:4px 0 12px}";
+
+pub fn render_html(
+    context: &ReviewExportContext<'_>,
+    entry: Option<&TaskReviewEntry>,
+    files: &[DiffFile],
+) -> String {
+    let stats = file_stats(files);
+    let title = escape_html(context.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Review: {title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n\
+         <h1>Review: {title}</h1>\n"
+    );
+    out.push_str(&format!(
+        "<ul>\n<li>Branch: <code>{}</code> (base <code>{}</code> at <code>{}</code>)</li>\n\
+         <li>Verdict: {}</li>\n<li>Threads: {}</li>\n<li>Exported: {}</li>\n</ul>\n",
+        escape_html(context.branch_name),
+        escape_html(context.base_branch),
+        escape_html(short_commit(context.base_commit)),
+        escape_html(&verdict_line(entry)),
+        thread_summary(entry),
+        timestamp(Utc::now())
+    ));
+
+    let additions: usize = stats.iter().map(|file| file.additions).sum();
+    let deletions: usize = stats.iter().map(|file| file.deletions).sum();
+    out.push_str(&format!(
+        "<h2>Changes</h2>\n<p>{} file(s) changed, <span class=\"add\">+{additions}</span> \
+         <span class=\"del\">-{deletions}</span></p>\n",
+        stats.len()
+    ));
+    if !stats.is_empty() {
+        out.push_str("<table>\n<tr><th>File</th><th>Status</th><th>+</th><th>-</th></tr>\n");
+        for file in &stats {
+            out.push_str(&format!(
+                "<tr><td><code>{}</code></td><td>{}</td><td class=\"num add\">{}</td>\
+                 <td class=\"num del\">{}</td></tr>\n",
+                escape_html(&file.path),
+                escape_html(&file.status),
+                file.additions,
+                file.deletions
+            ));
+        }
+        out.push_str("</table>\n");
+    }
+
+    out.push_str("<h2>Threads</h2>\n");
+    let threads = entry.map(sorted_threads).unwrap_or_default();
+    if threads.is_empty() {
+        out.push_str("<p>No review threads.</p>\n");
+    }
+    for (index, thread) in threads.iter().enumerate() {
+        out.push_str(&format!(
+            "<div class=\"thread\">\n<h3>{}. {} <span class=\"status\">{}{}</span></h3>\n",
+            index + 1,
+            escape_html(&thread_location(thread)),
+            status_label(thread.status),
+            if thread.outdated { ", outdated" } else { "" }
+        ));
+        if let Some(code) = thread_code(thread, files) {
+            if code.from_anchor {
+                out.push_str("<p class=\"meta\">Code as it was when the thread was started:</p>\n");
+            }
+            out.push_str("<pre class=\"code\">");
+            for line in &code.lines {
+                let class = match (line.in_range, line.marker) {
+                    (true, _) => " class=\"focus\"",
+                    (false, '+') => " class=\"add\"",
+                    (false, '-') => " class=\"del\"",
+                    _ => "",
+                };
+                out.push_str(&format!(
+                    "<span{class}>{}</span>\n",
+                    escape_html(&code_line_text(line))
+                ));
+            }
+            out.push_str("</pre>\n");
+        }
+        for comment in &thread.comments {
+            out.push_str(&format!(
+                "<div class=\"meta\"><strong>{}</strong> · {}</div>\n\
+                 <div class=\"body\">{}</div>\n",
+                escape_html(&author_label(comment)),
+                timestamp(comment.created_at),
+                escape_html(comment.body.trim())
+            ));
+            if let Some(suggestion) = &comment.suggestion {
+                let state = if suggestion.applied_at.is_some() {
+                    "applied"
+                } else {
+                    "not applied"
+                };
+                out.push_str(&format!(
+                    "<div class=\"meta\">Suggested replacement ({state}):</div>\n\
+                     <pre class=\"code\">{}</pre>\n",
+                    escape_html(suggestion.replacement.trim_end_matches(['\r', '\n']))
+                ));
+            }
+        }
+        out.push_str("</div>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
@@ -0,0 +1,80 @@
+use super::{load_store, ReviewVerdict};
+use crate::error::{Result, TaskError};
+use crate::features::settings::try_load_settings_section;
+use crate::utils::path::normalize_path_string;
+use serde::Deserialize;
+use std::path::Path;
+
+/// One `[[review.gates]]` entry: requires an approved review before pushing from the repo.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReviewGateSettings {
+    /// Path of the repository's main checkout.
+    pub repo: String,
+    /// Also gate commits, not just pushes.
+    #[serde(default)]
+    pub commit: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatedAction {
+    Commit,
+    Push,
+}
+
+/// The gate configured for `base_repo_path`, if any. Settings that cannot be read are an
+/// error rather than "no gate", so a typo never turns enforcement off.
+pub fn gate_for_repo(
+    app: &tauri::AppHandle,
+    base_repo_path: &Path,
+) -> Result<Option<ReviewGateSettings>> {
+    let gates: Vec<ReviewGateSettings> =
+        try_load_settings_section(app, "review.gates").map_err(|err| {
+            TaskError::Message(format!("The review gate settings are invalid: {err:#}"))
+        })?;
+    let target = normalize_path_string(base_repo_path);
+    Ok(gates.into_iter().find(|gate| {
+        !gate.repo.trim().is_empty()
+            && normalize_path_string(Path::new(gate.repo.trim())).trim_end_matches(['/', '\\'])
+                == target.trim_end_matches(['/', '\\'])
+    }))
+}
+
+/// Refuses `action` while the repo's gate applies and the task's review is not finished:
+/// threads are still active or pending, or the verdict is not approved. Also refuses it when
+/// the gate settings are invalid. The verdict comes from the base repository's verdict store.
+pub fn enforce_review_gate(
+    app: &tauri::AppHandle,
+    base_repo_path: &Path,
+    worktree_root: &Path,
+    task_id: &str,
+    action: GatedAction,
+) -> Result<()> {
+    let Some(gate) = gate_for_repo(app, base_repo_path)? else {
+        return Ok(());
+    };
+    if action == GatedAction::Commit && !gate.commit {
+        return Ok(());
+    }
+    let store = load_store(worktree_root)?;
+    let (verdict, open_threads) = store
+        .tasks
+        .get(task_id)
+        .map(|entry| (entry.verdict, entry.open_thread_count()))
+        .unwrap_or_default();
+    let action = match action {
+        GatedAction::Commit => "commit",
+        GatedAction::Push => "push",
+    };
+    if open_threads > 0 {
+        return Err(TaskError::Message(format!(
+            "Cannot {action}: {open_threads} review thread(s) are still active or pending."
+        )));
+    }
+    if verdict != ReviewVerdict::Approved {
+        return Err(TaskError::Message(format!(
+            "Cannot {action}: the task's review is not approved."
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::features::settings::load_settings_section;
+use crate::features::tasks::git::get_user_identity;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// `[review.identity]`: overrides the git identity used for review comments.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReviewIdentitySettings {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewIdentity {
+    pub name: String,
+    pub email: Option<String>,
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn system_user_name() -> String {
+    let realname = whoami::realname();
+    let trimmed = realname.trim();
+    if !trimmed.is_empty() {
+        return trimmed.to_string();
+    }
+    whoami::username()
+}
+
+/// Resolves who is writing review comments: settings first, then the git config of
+/// `worktree_root` (or the global git config), then the OS account.
+pub fn resolve_identity(app: &tauri::AppHandle, worktree_root: Option<&Path>) -> ReviewIdentity {
+    let settings: ReviewIdentitySettings = load_settings_section(app, "review.identity");
+    let (git_name, git_email) = get_user_identity(worktree_root).unwrap_or_else(|err| {
+        warn!("failed to read git user identity: {}", err);
+        (None, None)
+    });
+    ReviewIdentity {
+        name: non_empty(settings.name)
+            .or(git_name)
+            .unwrap_or_else(system_user_name),
+        email: non_empty(settings.email).or(git_email),
+    }
+}
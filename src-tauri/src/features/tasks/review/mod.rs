@@ -1,14 +1,29 @@
+pub mod anchor;
+pub mod auto_review;
+pub mod cli;
 pub mod commands;
+pub mod export;
+pub mod gate;
+pub mod identity;
 pub mod skills;
+pub mod submit;
+pub mod suggestion;
+pub mod verdict;
+pub mod viewed;
 
 use crate::error::Result;
+use crate::features::tasks::AgentKind;
 use crate::utils::fs::ensure_illuc_dir;
+use crate::utils::json_store::{self, StoreSchema};
 use chrono::{DateTime, Utc};
+use git2::{ObjectType, Oid};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
-const REVIEW_VERSION: u32 = 1;
+const REVIEW_VERSION: u32 = 4;
 const REVIEW_FILE: &str = "local-review.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,19 +47,177 @@ impl Default for ReviewStore {
 pub struct TaskReviewEntry {
     pub task_id: String,
     pub threads: Vec<ReviewThread>,
+    /// Filled from the base repository's verdict store and kept out of this file; commands
+    /// return it through `TaskReviewView`.
+    #[serde(skip)]
+    pub verdict: ReviewVerdict,
+    #[serde(skip)]
+    pub verdict_by: Option<String>,
+    #[serde(skip)]
+    pub verdict_at: Option<DateTime<Utc>>,
+    /// Files marked as viewed, by path.
+    #[serde(default)]
+    pub viewed_files: HashMap<String, ViewedFile>,
+}
+
+/// A "viewed" mark; it only counts while the file still has the same content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewedFile {
+    pub blob_id: String,
+    pub viewed_at: DateTime<Utc>,
+}
+
+impl TaskReviewEntry {
+    pub fn new(task_id: String) -> Self {
+        Self {
+            task_id,
+            threads: Vec::new(),
+            verdict: ReviewVerdict::default(),
+            verdict_by: None,
+            verdict_at: None,
+            viewed_files: HashMap::new(),
+        }
+    }
+
+    /// Threads that still wait on someone: active or pending.
+    pub fn open_thread_count(&self) -> usize {
+        self.threads
+            .iter()
+            .filter(|thread| {
+                matches!(
+                    thread.status,
+                    ReviewCommentStatus::Active | ReviewCommentStatus::Pending
+                )
+            })
+            .count()
+    }
+}
+
+/// The review store as commands return it, with each task's verdict.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewStoreView {
+    pub version: u32,
+    pub tasks: HashMap<String, TaskReviewView>,
+}
+
+impl From<ReviewStore> for ReviewStoreView {
+    fn from(store: ReviewStore) -> Self {
+        Self {
+            version: store.version,
+            tasks: store
+                .tasks
+                .into_iter()
+                .map(|(task_id, entry)| (task_id, entry.into()))
+                .collect(),
+        }
+    }
+}
+
+/// A task's review entry as commands return it, with the verdict the store file leaves out.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskReviewView {
+    #[serde(flatten)]
+    pub entry: TaskReviewEntry,
+    pub verdict: ReviewVerdict,
+    pub verdict_by: Option<String>,
+    pub verdict_at: Option<DateTime<Utc>>,
+}
+
+impl From<TaskReviewEntry> for TaskReviewView {
+    fn from(entry: TaskReviewEntry) -> Self {
+        Self {
+            verdict: entry.verdict,
+            verdict_by: entry.verdict_by.clone(),
+            verdict_at: entry.verdict_at,
+            entry,
+        }
+    }
+}
+
+/// The reviewer's overall decision on a task.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReviewVerdict {
+    #[default]
+    Unreviewed,
+    ChangesRequested,
+    Approved,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReviewThread {
+    pub id: String,
+    #[serde(default)]
+    pub scope: ReviewScope,
+    /// Empty for task-level threads.
     pub file_path: String,
     pub line_number_old: Option<u32>,
     pub line_number_new: Option<u32>,
+    #[serde(default)]
     pub line_type: ReviewLineType,
+    /// Lines covered by a line thread; the last line is the one at the line numbers above.
+    #[serde(default)]
+    pub range: Option<ReviewRange>,
     #[serde(default)]
     pub status: ReviewCommentStatus,
     #[serde(default)]
     pub comments: Vec<ReviewComment>,
+    /// Content the thread was left on, used to follow the line when the code moves.
+    #[serde(default)]
+    pub anchor: Option<ReviewAnchor>,
+    /// The anchored line no longer exists in the task's diff.
+    #[serde(default)]
+    pub outdated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewAnchor {
+    pub line: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+    /// Every line of a multi-line range, first to last; empty for single-line threads.
+    #[serde(default)]
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSide {
+    Old,
+    New,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewRange {
+    pub side: ReviewSide,
+    pub start: u32,
+    pub end: u32,
+}
+
+impl ReviewRange {
+    /// The one-line range at a diff row; the new side wins when the row has both numbers.
+    pub fn single(line_number_old: Option<u32>, line_number_new: Option<u32>) -> Option<Self> {
+        let (side, line) = match (line_number_old, line_number_new) {
+            (_, Some(line)) => (ReviewSide::New, line),
+            (Some(line), None) => (ReviewSide::Old, line),
+            (None, None) => return None,
+        };
+        Some(Self {
+            side,
+            start: line,
+            end: line,
+        })
+    }
+
+    pub fn is_multi_line(&self) -> bool {
+        self.end > self.start
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -52,8 +225,36 @@ pub struct ReviewThread {
 pub struct ReviewComment {
     pub id: String,
     pub body: String,
+    /// Display name of whoever wrote the comment.
     pub author: String,
+    #[serde(default)]
+    pub author_email: Option<String>,
+    #[serde(default)]
+    pub author_kind: ReviewAuthorKind,
+    /// Set on replies written by an agent.
+    #[serde(default)]
+    pub agent_kind: Option<AgentKind>,
     pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub suggestion: Option<ReviewSuggestion>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewAuthorKind {
+    #[default]
+    User,
+    Agent,
+}
+
+/// Replacement text proposed for the lines of a new-side line thread.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewSuggestion {
+    /// Replaces every line of the thread's range; empty to delete them.
+    pub replacement: String,
+    #[serde(default)]
+    pub applied_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -72,34 +273,160 @@ impl Default for ReviewCommentStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ReviewLineType {
     Add,
     Del,
+    #[default]
     Context,
     Meta,
     Hunk,
 }
 
-pub fn load_store(worktree_root: &Path) -> Result<ReviewStore> {
-    let path = review_path(worktree_root)?;
-    if !path.exists() {
-        return Ok(ReviewStore::default());
+/// What a thread is attached to.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewScope {
+    #[default]
+    Line,
+    File,
+    Task,
+}
+
+const REVIEW_SCHEMA: StoreSchema = StoreSchema {
+    version: REVIEW_VERSION,
+    migrations: &[
+        (1, migrate_v1_thread_ids),
+        (2, migrate_v2_thread_ranges),
+        (3, migrate_v3_comment_authors),
+    ],
+};
+
+fn for_each_thread(value: &mut Value, mut apply: impl FnMut(&mut Map<String, Value>)) {
+    let tasks = value.get_mut("tasks").and_then(Value::as_object_mut);
+    for entry in tasks.into_iter().flat_map(|tasks| tasks.values_mut()) {
+        let Some(threads) = entry.get_mut("threads").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for thread in threads.iter_mut().filter_map(Value::as_object_mut) {
+            apply(thread);
+        }
     }
-    let contents = std::fs::read_to_string(&path)?;
-    let parsed: ReviewStore = serde_json::from_str(&contents).unwrap_or_default();
-    if parsed.version != REVIEW_VERSION {
-        return Ok(ReviewStore::default());
+}
+
+/// Version 2 gives every thread a stable id. The id is derived from the task, the thread's
+/// position and its line, so every reader migrating the same file (the app or the helper
+/// script) hands out the same ids.
+fn migrate_v1_thread_ids(mut value: Value) -> Result<Value> {
+    let tasks = value.get_mut("tasks").and_then(Value::as_object_mut);
+    for (task_key, entry) in tasks.into_iter().flatten() {
+        let Some(threads) = entry.get_mut("threads").and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for (index, thread) in threads.iter_mut().enumerate() {
+            let Some(thread) = thread.as_object_mut() else {
+                continue;
+            };
+            if thread.get("id").is_some_and(Value::is_string) {
+                continue;
+            }
+            let line = |key: &str| {
+                thread
+                    .get(key)
+                    .and_then(Value::as_u64)
+                    .map(|line| line as u32)
+            };
+            let file_path = thread
+                .get("filePath")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let key = format!(
+                "{task_key}::{index}::{}",
+                thread_key(file_path, line("lineNumberOld"), line("lineNumberNew"))
+            );
+            thread.insert("id".to_string(), Value::from(derived_thread_id(&key)?));
+        }
     }
-    Ok(parsed)
+    Ok(value)
+}
+
+/// A UUID-shaped id from the first 16 bytes of the key's git blob hash.
+fn derived_thread_id(key: &str) -> Result<String> {
+    let hash = Oid::hash_object(ObjectType::Blob, key.as_bytes()).map_err(anyhow::Error::from)?;
+    let id = Uuid::from_slice(&hash.as_bytes()[..16]).map_err(anyhow::Error::from)?;
+    Ok(id.to_string())
+}
+
+/// Version 3 stores the covered line range; existing threads cover their single line. A thread
+/// without a line number has nothing to cover and becomes a file thread.
+fn migrate_v2_thread_ranges(mut value: Value) -> Result<Value> {
+    for_each_thread(&mut value, |thread| {
+        let line = |key: &str| {
+            thread
+                .get(key)
+                .and_then(Value::as_u64)
+                .map(|line| line as u32)
+        };
+        match ReviewRange::single(line("lineNumberOld"), line("lineNumberNew")) {
+            Some(range) => {
+                thread.insert("range".to_string(), serde_json::json!(range));
+            }
+            None => {
+                thread.insert("scope".to_string(), serde_json::json!(ReviewScope::File));
+            }
+        }
+    });
+    Ok(value)
+}
+
+/// Version 4 tells user and agent comments apart. Comments used to be written as `user` by the
+/// app and under the agent's name by the helper script.
+fn migrate_v3_comment_authors(mut value: Value) -> Result<Value> {
+    for_each_thread(&mut value, |thread| {
+        let comments = thread.get_mut("comments").and_then(Value::as_array_mut);
+        for comment in comments
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_object_mut)
+        {
+            let author = comment
+                .get("author")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            let (kind, agent) = match author.to_ascii_lowercase().as_str() {
+                "user" => ("user", None),
+                "codex" => ("agent", Some("codex")),
+                "copilot" => ("agent", Some("copilot")),
+                _ => ("agent", None),
+            };
+            comment.insert("authorKind".to_string(), Value::from(kind));
+            comment.insert(
+                "agentKind".to_string(),
+                agent.map(Value::from).unwrap_or(Value::Null),
+            );
+        }
+    });
+    Ok(value)
+}
+
+pub fn load_store(worktree_root: &Path) -> Result<ReviewStore> {
+    let mut store: ReviewStore = json_store::load(&review_path(worktree_root)?, &REVIEW_SCHEMA)?;
+    verdict::apply_verdicts(&mut store, &verdict::load_verdicts(worktree_root)?);
+    Ok(store)
 }
 
-pub fn save_store(worktree_root: &Path, store: &ReviewStore) -> Result<()> {
-    let path = review_path(worktree_root)?;
-    let payload = serde_json::to_string_pretty(store).map_err(|err| anyhow::Error::from(err))?;
-    std::fs::write(path, payload)?;
-    Ok(())
+/// Applies `update` to the store while holding its lock, then writes the result back.
+pub fn update_store<R, F>(worktree_root: &Path, update: F) -> Result<R>
+where
+    F: FnOnce(&mut ReviewStore) -> Result<R>,
+{
+    let verdicts = verdict::load_verdicts(worktree_root)?;
+    json_store::update(&review_path(worktree_root)?, &REVIEW_SCHEMA, |store| {
+        verdict::apply_verdicts(store, &verdicts);
+        update(store)
+    })
 }
 
 fn review_path(worktree_root: &Path) -> Result<PathBuf> {
@@ -121,6 +448,42 @@ pub fn thread_key(
     format!("{file_path}::{old}::{new}")
 }
 
+/// Task threads first, then per file its file-level threads before its line threads.
+pub fn sorted_threads(entry: &TaskReviewEntry) -> Vec<&ReviewThread> {
+    let mut threads: Vec<&ReviewThread> = entry.threads.iter().collect();
+    threads.sort_by_key(|thread| {
+        let scope_order = match thread.scope {
+            ReviewScope::Task => 0,
+            ReviewScope::File => 1,
+            ReviewScope::Line => 2,
+        };
+        (
+            thread.scope != ReviewScope::Task,
+            thread.file_path.clone(),
+            scope_order,
+            thread.range.map(|range| range.end).unwrap_or(0),
+        )
+    });
+    threads
+}
+
+/// Finds a thread by id when given, otherwise by its position.
+pub fn locate_thread_mut<'a>(
+    entry: &'a mut TaskReviewEntry,
+    thread_id: Option<&str>,
+    file_path: &str,
+    line_number_old: Option<u32>,
+    line_number_new: Option<u32>,
+) -> Option<&'a mut ReviewThread> {
+    match thread_id {
+        Some(thread_id) => entry
+            .threads
+            .iter_mut()
+            .find(|thread| thread.id == thread_id),
+        None => find_thread_mut(entry, file_path, line_number_old, line_number_new),
+    }
+}
+
 pub fn find_thread_mut<'a>(
     entry: &'a mut TaskReviewEntry,
     file_path: &str,
@@ -128,8 +491,68 @@ pub fn find_thread_mut<'a>(
     line_number_new: Option<u32>,
 ) -> Option<&'a mut ReviewThread> {
     entry.threads.iter_mut().find(|thread| {
-        thread.file_path == file_path
+        thread.scope == ReviewScope::Line
+            && thread.file_path == file_path
             && thread.line_number_old == line_number_old
             && thread.line_number_new == line_number_new
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{load_store, ReviewScope, REVIEW_FILE};
+    use crate::error::Result;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const V1_STORE: &str = r#"{
+        "version": 1,
+        "tasks": {
+            "t1": {
+                "taskId": "t1",
+                "threads": [{
+                    "filePath": "src/a.rs",
+                    "lineNumberOld": null,
+                    "lineNumberNew": 3,
+                    "comments": []
+                }, {
+                    "filePath": "src/b.rs",
+                    "lineNumberOld": null,
+                    "lineNumberNew": null,
+                    "comments": []
+                }]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn v1_thread_ids_are_the_same_on_every_load() -> Result<()> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!("illuc-review-test-{nanos}"));
+        let mut ids = Vec::new();
+        for copy in ["first", "second"] {
+            let worktree = root.join(copy);
+            std::fs::create_dir_all(worktree.join(".illuc"))?;
+            git2::Repository::init(&worktree).map_err(anyhow::Error::from)?;
+            std::fs::write(worktree.join(".illuc").join(REVIEW_FILE), V1_STORE)?;
+            for _ in 0..2 {
+                let store = load_store(&worktree)?;
+                let threads = &store.tasks["t1"].threads;
+                ids.push(threads[0].id.clone());
+                // A thread without a line survives the range migration as a file thread.
+                assert_eq!(threads.len(), 2);
+                assert_eq!(threads[1].scope, ReviewScope::File);
+            }
+        }
+
+        // Also what the helper script derives for this thread.
+        assert!(ids
+            .iter()
+            .all(|id| id == "23238ff3-a285-a180-7840-08f2f3cec972"));
+
+        std::fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -3,15 +3,18 @@ from __future__ import annotations
 
 import argparse
 import datetime as dt
+import hashlib
 import json
 import os
 import re
 import subprocess
 import sys
+import tempfile
 import uuid
+from contextlib import contextmanager
 from dataclasses import dataclass
 from pathlib import Path
-from typing import Any, Optional
+from typing import Any, Callable, Iterator, Optional
 
 
 UUID_RE = re.compile(
@@ -19,6 +22,94 @@ UUID_RE = re.compile(
     re.IGNORECASE,
 )
 
+# Keep in sync with REVIEW_VERSION in src/features/tasks/review/mod.rs.
+STORE_VERSION = 4
+
+# Lines of context stored around an anchored line (matches ANCHOR_CONTEXT_LINES in Rust).
+ANCHOR_CONTEXT_LINES = 3
+
+
+def derived_thread_id(key: str) -> str:
+    # Matches derived_thread_id in Rust: the first 16 bytes of the key's git blob hash.
+    data = key.encode("utf-8")
+    digest = hashlib.sha1(b"blob %d\x00" % len(data) + data).digest()
+    return str(uuid.UUID(bytes=digest[:16]))
+
+
+def migrate_v1_thread_ids(raw: dict[str, Any]) -> dict[str, Any]:
+    # Version 2 gives every thread a stable id, derived from the task, the thread's position and
+    # its line so the app and this script hand out the same ids.
+    tasks = raw.get("tasks")
+    for task_key, entry in (tasks.items() if isinstance(tasks, dict) else []):
+        threads = entry.get("threads") if isinstance(entry, dict) else None
+        for index, thread in enumerate(threads if isinstance(threads, list) else []):
+            if isinstance(thread, dict) and not isinstance(thread.get("id"), str):
+                file_path = thread.get("filePath")
+                line_old = thread.get("lineNumberOld")
+                line_new = thread.get("lineNumberNew")
+                line_key = "{}::{}::{}".format(
+                    file_path if isinstance(file_path, str) else "",
+                    line_old if isinstance(line_old, int) else "x",
+                    line_new if isinstance(line_new, int) else "x",
+                )
+                thread["id"] = derived_thread_id(f"{task_key}::{index}::{line_key}")
+    return raw
+
+
+def single_line_range(line_old: Optional[int], line_new: Optional[int]) -> Optional[dict[str, Any]]:
+    if line_new is not None:
+        return {"side": "new", "start": line_new, "end": line_new}
+    if line_old is not None:
+        return {"side": "old", "start": line_old, "end": line_old}
+    return None
+
+
+def migrate_v2_thread_ranges(raw: dict[str, Any]) -> dict[str, Any]:
+    # Version 3 stores the covered line range; existing threads cover their single line.
+    tasks = raw.get("tasks")
+    for entry in (tasks.values() if isinstance(tasks, dict) else []):
+        threads = entry.get("threads") if isinstance(entry, dict) else None
+        for thread in (threads if isinstance(threads, list) else []):
+            if not isinstance(thread, dict) or isinstance(thread.get("range"), dict):
+                continue
+            old = thread.get("lineNumberOld")
+            new = thread.get("lineNumberNew")
+            line_range = single_line_range(
+                old if isinstance(old, int) else None,
+                new if isinstance(new, int) else None,
+            )
+            if line_range is None:
+                raise RuntimeError("review thread without a line number")
+            thread["range"] = line_range
+    return raw
+
+
+AGENT_KINDS = ("codex", "copilot")
+
+
+def migrate_v3_comment_authors(raw: dict[str, Any]) -> dict[str, Any]:
+    # Version 4 tells user and agent comments apart.
+    tasks = raw.get("tasks")
+    for entry in (tasks.values() if isinstance(tasks, dict) else []):
+        threads = entry.get("threads") if isinstance(entry, dict) else None
+        for thread in (threads if isinstance(threads, list) else []):
+            comments = thread.get("comments") if isinstance(thread, dict) else None
+            for comment in (comments if isinstance(comments, list) else []):
+                if not isinstance(comment, dict):
+                    continue
+                author = str(comment.get("author") or "").lower()
+                comment["authorKind"] = "user" if author == "user" else "agent"
+                comment["agentKind"] = author if author in AGENT_KINDS else None
+    return raw
+
+
+# Upgrades a raw store by one version, keyed by the version it upgrades from.
+MIGRATIONS: dict[int, Callable[[dict[str, Any]], dict[str, Any]]] = {
+    1: migrate_v1_thread_ids,
+    2: migrate_v2_thread_ranges,
+    3: migrate_v3_comment_authors,
+}
+
 
 def iso_utc_now() -> str:
     # Match typical RFC3339 serialization used by serde/chrono (e.g. "...Z").
@@ -47,19 +138,89 @@ def read_json(path: Path) -> Any:
 
 
 def write_json(path: Path, value: Any) -> None:
+    # Write to a temp file in the same directory and rename it over the store, so readers
+    # never observe a half-written file.
     path.parent.mkdir(parents=True, exist_ok=True)
     payload = json.dumps(value, indent=2, ensure_ascii=True) + "\n"
-    path.write_text(payload, encoding="utf-8")
+    fd, tmp_name = tempfile.mkstemp(prefix=f"{path.name}.tmp-", dir=str(path.parent))
+    try:
+        with os.fdopen(fd, "w", encoding="utf-8") as f:
+            f.write(payload)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp_name, path)
+    except BaseException:
+        try:
+            os.unlink(tmp_name)
+        except OSError:
+            pass
+        raise
+
+
+@contextmanager
+def store_lock(store_path: Path) -> Iterator[None]:
+    # Same advisory lock file the app uses (<store>.lock), held for the whole command.
+    lock_path = store_path.with_name(store_path.name + ".lock")
+    lock_path.parent.mkdir(parents=True, exist_ok=True)
+    with lock_path.open("a+b") as handle:
+        if os.name == "nt":
+            import msvcrt
+
+            handle.seek(0)
+            while True:
+                try:
+                    msvcrt.locking(handle.fileno(), msvcrt.LK_LOCK, 1)
+                    break
+                except OSError:
+                    continue
+            try:
+                yield
+            finally:
+                handle.seek(0)
+                msvcrt.locking(handle.fileno(), msvcrt.LK_UNLCK, 1)
+        else:
+            import fcntl
+
+            fcntl.flock(handle.fileno(), fcntl.LOCK_EX)
+            try:
+                yield
+            finally:
+                fcntl.flock(handle.fileno(), fcntl.LOCK_UN)
+
+
+def backup_unreadable(store_path: Path, reason: str) -> None:
+    stamp = dt.datetime.now(dt.timezone.utc).strftime("%Y%m%dT%H%M%S%fZ")
+    backup = store_path.with_name(f"{store_path.name}.corrupt-{stamp}")
+    os.replace(store_path, backup)
+    print(f"{store_path} could not be read ({reason}); moved it to {backup}", file=sys.stderr)
+
+
+def migrate_store(raw: dict[str, Any]) -> Optional[dict[str, Any]]:
+    version = raw.get("version")
+    version = version if isinstance(version, int) else 0
+    if version > STORE_VERSION:
+        raise RuntimeError(
+            f"Review store uses schema version {version}, but this helper only supports up to {STORE_VERSION}."
+        )
+    while version < STORE_VERSION:
+        migrate = MIGRATIONS.get(version)
+        if migrate is None:
+            return None
+        raw = migrate(raw)
+        version += 1
+        raw["version"] = version
+    return raw
 
 
 def ensure_store(raw: Any) -> dict[str, Any]:
     if not isinstance(raw, dict):
-        return {"version": 1, "tasks": {}}
-    tasks = raw.get("tasks")
-    return {
-        "version": raw.get("version", 1) if isinstance(raw.get("version"), int) else 1,
-        "tasks": tasks if isinstance(tasks, dict) else {},
-    }
+        return {"version": STORE_VERSION, "tasks": {}}
+    # Keep unknown keys so newer fields written by the app survive a round trip.
+    store = dict(raw)
+    store["version"] = STORE_VERSION
+    if not isinstance(store.get("tasks"), dict):
+        store["tasks"] = {}
+    return store
 
 
 def infer_task_id(explicit: Optional[str], worktree_root: Path, store: dict[str, Any]) -> Optional[str]:
@@ -80,19 +241,27 @@ def infer_task_id(explicit: Optional[str], worktree_root: Path, store: dict[str,
 
 @dataclass
 class Thread:
+    id: str
+    scope: str
     file_path: str
     line_number_old: Optional[int]
     line_number_new: Optional[int]
     line_type: str
     status: str
     comments: list[dict[str, Any]]
+    range: dict[str, Any]
+    outdated: bool = False
+    anchor: Optional[dict[str, Any]] = None
 
 
 def normalize_thread(raw: Any) -> Thread:
     if not isinstance(raw, dict):
         raise RuntimeError("Invalid thread shape in store")
+    scope = raw.get("scope") or "line"
     file_path = raw.get("filePath", raw.get("file_path"))
-    if not isinstance(file_path, str) or not file_path.strip():
+    if scope == "task":
+        file_path = ""
+    elif not isinstance(file_path, str) or not file_path.strip():
         raise RuntimeError("Invalid thread filePath")
     lno = raw.get("lineNumberOld", raw.get("line_number_old"))
     lnn = raw.get("lineNumberNew", raw.get("line_number_new"))
@@ -103,28 +272,90 @@ def normalize_thread(raw: Any) -> Thread:
     comments = raw.get("comments")
     if not isinstance(comments, list):
         comments = []
+    thread_id = raw.get("id")
+    line_range = raw.get("range")
+    if not isinstance(line_range, dict):
+        line_range = single_line_range(line_number_old, line_number_new) or {}
+    anchor = raw.get("anchor")
     return Thread(
+        id=thread_id if isinstance(thread_id, str) else "",
+        scope=str(scope),
         file_path=file_path,
         line_number_old=line_number_old,
         line_number_new=line_number_new,
         line_type=str(line_type),
         status=str(status),
         comments=comments,
+        range=line_range,
+        outdated=raw.get("outdated") is True,
+        anchor=anchor if isinstance(anchor, dict) else None,
     )
 
 
+def format_range(t: Thread) -> str:
+    if not t.range:
+        return "-"
+    start, end = t.range.get("start"), t.range.get("end")
+    span = f"{start}" if start == end else f"{start}-{end}"
+    return f"{t.range.get('side', 'new')}:{span}"
+
+
+def read_lines(worktree_root: Path, file_path: str) -> Optional[list[str]]:
+    try:
+        return (worktree_root / file_path).read_text(encoding="utf-8").splitlines()
+    except (OSError, UnicodeDecodeError):
+        return None
+
+
+def anchor_from_file(worktree_root: Path, file_path: str, line_range: dict[str, Any]) -> Optional[dict[str, Any]]:
+    # Same shape the app stores, so the thread can follow its lines when the code moves.
+    if line_range.get("side") != "new":
+        return None
+    lines = read_lines(worktree_root, file_path)
+    index = int(line_range["end"]) - 1
+    if lines is None or index < 0 or index >= len(lines):
+        return None
+    start = min(max(0, int(line_range["start"]) - 1), index)
+    return {
+        "line": lines[index],
+        "contextBefore": lines[max(0, index - ANCHOR_CONTEXT_LINES) : index],
+        "contextAfter": lines[index + 1 : index + 1 + ANCHOR_CONTEXT_LINES],
+        "lines": lines[start : index + 1] if start < index else [],
+    }
+
+
 def thread_key(t: Thread) -> str:
+    if t.scope == "task":
+        return "task"
+    if t.scope == "file":
+        return f"{t.file_path}::file"
     old_part = "x" if t.line_number_old is None else str(t.line_number_old)
     new_part = "x" if t.line_number_new is None else str(t.line_number_new)
     return f"{t.file_path}::{old_part}::{new_part}"
 
 
+def thread_identity(t: Thread) -> str:
+    # Several file/task threads can share a key, so prefer the stable id.
+    return t.id or thread_key(t)
+
+
+SCOPE_ORDER = {"task": 0, "file": 1, "line": 2}
+
+
 def list_threads(entry: Any) -> list[Thread]:
     threads_raw = entry.get("threads") if isinstance(entry, dict) else None
     if not isinstance(threads_raw, list):
         return []
     threads = [normalize_thread(t) for t in threads_raw]
-    threads.sort(key=lambda t: (t.file_path, t.line_number_new or 0))
+    # Task-level threads first, then per file: file-level threads before line threads.
+    threads.sort(
+        key=lambda t: (
+            t.scope != "task",
+            t.file_path,
+            SCOPE_ORDER.get(t.scope, 2),
+            t.line_number_new or 0,
+        )
+    )
     return threads
 
 
@@ -139,6 +370,16 @@ def latest_comment_preview(t: Thread) -> str:
     return first.replace('"', "'")
 
 
+def open_suggestions(t: Thread) -> int:
+    return sum(
+        1
+        for comment in t.comments
+        if isinstance(comment, dict)
+        and isinstance(comment.get("suggestion"), dict)
+        and not comment["suggestion"].get("appliedAt")
+    )
+
+
 def cmd_list(store_path: Path, store: dict[str, Any], task_id: Optional[str], statuses: list[str]) -> int:
     if not task_id:
         if not store_path.exists():
@@ -163,21 +404,32 @@ def cmd_list(store_path: Path, store: dict[str, Any], task_id: Optional[str], st
         return 0
 
     # Stable indices are based on the full sorted thread list.
-    index_by_key = {thread_key(t): i for i, t in enumerate(all_threads)}
+    index_by_key = {thread_identity(t): i for i, t in enumerate(all_threads)}
     for t in visible:
-        idx = index_by_key.get(thread_key(t), -1)
+        idx = index_by_key.get(thread_identity(t), -1)
         preview = latest_comment_preview(t)
+        if t.scope == "line":
+            target = [
+                f"file={t.file_path}",
+                f"old={t.line_number_old if t.line_number_old is not None else 'x'}",
+                f"new={t.line_number_new if t.line_number_new is not None else 'x'}",
+                f"type={t.line_type}",
+                f"range={format_range(t)}",
+            ]
+        elif t.scope == "file":
+            target = ["scope=file", f"file={t.file_path}"]
+        else:
+            target = ["scope=task"]
         print(
             " ".join(
                 p
                 for p in [
                     f"#{idx}",
                     f"status={t.status}",
-                    f"file={t.file_path}",
-                    f"old={t.line_number_old if t.line_number_old is not None else 'x'}",
-                    f"new={t.line_number_new if t.line_number_new is not None else 'x'}",
-                    f"type={t.line_type}",
+                    *target,
+                    ("outdated" if t.outdated else ""),
                     f"comments={len(t.comments)}",
+                    (f"suggestions={open_suggestions(t)}" if open_suggestions(t) else ""),
                     (f'latest="{preview}"' if preview else ""),
                 ]
                 if p
@@ -186,6 +438,55 @@ def cmd_list(store_path: Path, store: dict[str, Any], task_id: Optional[str], st
     return 0
 
 
+def cmd_show(worktree_root: Path, t: Thread, index: int) -> int:
+    if t.scope == "task":
+        target = "scope=task"
+    elif t.scope == "file":
+        target = f"scope=file file={t.file_path}"
+    else:
+        target = f"file={t.file_path} range={format_range(t)}"
+    print(f"#{index} status={t.status} {target}" + (" outdated" if t.outdated else ""))
+    start, end = int(t.range.get("start") or 0), int(t.range.get("end") or 0)
+    print("")
+    lines = read_lines(worktree_root, t.file_path) if t.range.get("side") == "new" else None
+    if lines is not None and not t.outdated and 0 < start <= end <= len(lines):
+        # Current worktree content of the range, with a little context around it.
+        first = max(1, start - ANCHOR_CONTEXT_LINES)
+        last = min(len(lines), end + ANCHOR_CONTEXT_LINES)
+        for number in range(first, last + 1):
+            marker = ">" if start <= number <= end else " "
+            print(f"{marker} {number:>5} | {lines[number - 1]}")
+    elif t.anchor:
+        # Deleted or outdated code: show what the comment was originally left on.
+        print("(code as it was when the comment was left)")
+        anchored = t.anchor.get("lines") or [t.anchor.get("line", "")]
+        for line in t.anchor.get("contextBefore") or []:
+            print(f"  {line}")
+        for line in anchored:
+            print(f"> {line}")
+        for line in t.anchor.get("contextAfter") or []:
+            print(f"  {line}")
+    elif t.scope == "task":
+        print("(applies to the task as a whole)")
+    elif t.scope == "file":
+        print(f"(applies to the whole of {t.file_path})")
+    else:
+        print("(code for this thread is not available)")
+    print("")
+    for comment in t.comments:
+        if not isinstance(comment, dict):
+            continue
+        print(f"--- {comment.get('author', '?')} at {comment.get('createdAt', '?')}")
+        print(str(comment.get("body", "")).rstrip())
+        suggestion = comment.get("suggestion")
+        if isinstance(suggestion, dict):
+            state = "applied" if suggestion.get("appliedAt") else "not applied"
+            print(f"suggested replacement for lines {format_range(t)} ({state}):")
+            for line in str(suggestion.get("replacement", "")).splitlines() or [""]:
+                print(f"+ {line}")
+    return 0
+
+
 def map_back_and_write(
     store_path: Path,
     store: dict[str, Any],
@@ -202,11 +503,11 @@ def map_back_and_write(
     if not isinstance(threads_raw, list):
         raise RuntimeError("Invalid store: threads")
 
-    key = thread_key(selected)
+    key = thread_identity(selected)
     idx = -1
     for i, raw in enumerate(threads_raw):
         try:
-            if thread_key(normalize_thread(raw)) == key:
+            if thread_identity(normalize_thread(raw)) == key:
                 idx = i
                 break
         except Exception:
@@ -214,12 +515,17 @@ def map_back_and_write(
     if idx == -1:
         raise RuntimeError(f"Failed to map normalized thread back into store: {key}")
 
-    # Keep store camelCase shape (matches Rust/TS).
+    # Keep store camelCase shape (matches Rust/TS) and any fields this helper doesn't know.
+    existing = threads_raw[idx] if isinstance(threads_raw[idx], dict) else {}
     threads_raw[idx] = {
+        **existing,
+        "id": selected.id,
+        "scope": selected.scope,
         "filePath": selected.file_path,
         "lineNumberOld": selected.line_number_old,
         "lineNumberNew": selected.line_number_new,
         "lineType": selected.line_type,
+        "range": selected.range or None,
         "status": selected.status,
         "comments": selected.comments,
     }
@@ -231,8 +537,18 @@ def map_back_and_write(
 
 def load_store(store_path: Path) -> dict[str, Any]:
     if store_path.exists():
-        raw = read_json(store_path)
-        return ensure_store(raw)
+        try:
+            raw = read_json(store_path)
+        except ValueError as e:
+            backup_unreadable(store_path, f"invalid JSON: {e}")
+            raw = None
+        if isinstance(raw, dict):
+            migrated = migrate_store(raw)
+            if migrated is not None:
+                return ensure_store(migrated)
+            backup_unreadable(store_path, f"no migration from version {raw.get('version')}")
+        elif raw is not None:
+            backup_unreadable(store_path, "unexpected shape")
     store = ensure_store(None)
     write_json(store_path, store)
     return store
@@ -248,11 +564,15 @@ def select_thread(entry: dict[str, Any], thread_index: int) -> Thread:
 
 
 def main(argv: list[str]) -> int:
+    # Installed as `illuc-cli` when the native binary is unavailable; accept its `review` prefix.
+    if argv[:1] == ["review"]:
+        argv = argv[1:]
     if "--help" in argv or "-h" in argv:
         print(
             "Usage:\n"
             "  python3 illuc-review.py list [--task <uuid>] [--status active,pending]\n"
-            "  python3 illuc-review.py add --file <path> [--line-new N|--line-old N] [--line-type add|del|context|meta|hunk] [--status active|pending|resolved|wont-fix|closed] --message \"<text>\" [--author <name>] [--task <uuid>]\n"
+            "  python3 illuc-review.py show --thread <n> [--task <uuid>]\n"
+            "  python3 illuc-review.py add [--scope line|file|task] [--file <path>] [--line-new N|--line-old N] [--range-start N] [--line-type add|del|context|meta|hunk] [--status active|pending|resolved|wont-fix|closed] --message \"<text>\" [--suggestion \"<replacement>\"] [--author <name>] [--task <uuid>]\n"
             "  python3 illuc-review.py comment --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]\n"
             "  python3 illuc-review.py resolve --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]\n"
             "  python3 illuc-review.py set-status --thread <n> --status <active|pending|resolved|wont-fix|closed> [--task <uuid>]\n"
@@ -261,6 +581,9 @@ def main(argv: list[str]) -> int:
             "  - Operates on <worktreeRoot>/.illuc/local-review.json (worktreeRoot is git toplevel).\n"
             "  - Task id is usually the worktree folder name (UUID). If not provided, we infer it.\n"
             "  - You can set a default author via env var ILLUC_REVIEW_AUTHOR.\n"
+            "  - --range-start makes the thread cover lines N..line on the same side (a multi-line comment).\n"
+            "  - --scope file comments on a whole file (--file only); --scope task on the task as a whole.\n"
+            "  - --suggestion proposes replacement text for the thread's lines (new-side line threads only).\n"
         )
         return 0
 
@@ -275,6 +598,9 @@ def main(argv: list[str]) -> int:
         p.add_argument("--task", type=str, default=None)
         p.add_argument("--thread", type=int, required=True)
 
+    p_show = sub.add_parser("show")
+    add_thread_args(p_show)
+
     p_comment = sub.add_parser("comment")
     add_thread_args(p_comment)
     p_comment.add_argument("--message", type=str, required=True)
@@ -296,9 +622,11 @@ def main(argv: list[str]) -> int:
 
     p_add = sub.add_parser("add")
     p_add.add_argument("--task", type=str, default=None)
-    p_add.add_argument("--file", type=str, required=True)
+    p_add.add_argument("--scope", type=str, default="line", choices=["line", "file", "task"])
+    p_add.add_argument("--file", type=str, default=None)
     p_add.add_argument("--line-old", type=int, default=None)
     p_add.add_argument("--line-new", type=int, default=None)
+    p_add.add_argument("--range-start", type=int, default=None)
     p_add.add_argument(
         "--line-type",
         type=str,
@@ -312,6 +640,7 @@ def main(argv: list[str]) -> int:
         choices=["active", "pending", "resolved", "wont-fix", "closed"],
     )
     p_add.add_argument("--message", type=str, required=True)
+    p_add.add_argument("--suggestion", type=str, default=None)
     p_add.add_argument("--author", type=str, default=None)
 
     p_help = sub.add_parser("--help")
@@ -324,7 +653,8 @@ def main(argv: list[str]) -> int:
         print(
             "Usage:\n"
             "  python3 illuc-review.py list [--task <uuid>] [--status active,pending]\n"
-            "  python3 illuc-review.py add --file <path> [--line-new N|--line-old N] [--line-type add|del|context|meta|hunk] [--status active|pending|resolved|wont-fix|closed] --message \"<text>\" [--author <name>] [--task <uuid>]\n"
+            "  python3 illuc-review.py show --thread <n> [--task <uuid>]\n"
+            "  python3 illuc-review.py add [--scope line|file|task] [--file <path>] [--line-new N|--line-old N] [--range-start N] [--line-type add|del|context|meta|hunk] [--status active|pending|resolved|wont-fix|closed] --message \"<text>\" [--suggestion \"<replacement>\"] [--author <name>] [--task <uuid>]\n"
             "  python3 illuc-review.py comment --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]\n"
             "  python3 illuc-review.py resolve --thread <n> [--task <uuid>] --message \"<text>\" [--author <name>]\n"
             "  python3 illuc-review.py set-status --thread <n> --status <active|pending|resolved|wont-fix|closed> [--task <uuid>]\n"
@@ -333,11 +663,19 @@ def main(argv: list[str]) -> int:
             "  - Operates on <worktreeRoot>/.illuc/local-review.json (worktreeRoot is git toplevel).\n"
             "  - Task id is usually the worktree folder name (UUID). If not provided, we infer it.\n"
             "  - You can set a default author via env var ILLUC_REVIEW_AUTHOR.\n"
+            "  - --range-start makes the thread cover lines N..line on the same side (a multi-line comment).\n"
+            "  - --scope file comments on a whole file (--file only); --scope task on the task as a whole.\n"
+            "  - --suggestion proposes replacement text for the thread's lines (new-side line threads only).\n"
         )
         return 0
 
     worktree_root = try_git_toplevel() or Path.cwd()
     store_path = worktree_root / ".illuc" / "local-review.json"
+    with store_lock(store_path):
+        return run_command(ns, worktree_root, store_path)
+
+
+def run_command(ns: argparse.Namespace, worktree_root: Path, store_path: Path) -> int:
     store = load_store(store_path)
     task_id = infer_task_id(ns.task, worktree_root, store)
 
@@ -350,31 +688,43 @@ def main(argv: list[str]) -> int:
     if not task_id:
         raise RuntimeError("Unable to infer task id. Pass --task <uuid>.")
 
-    def resolve_author(explicit: Optional[str]) -> str:
-        value = (explicit or "").strip()
-        if value:
-            return value
-        env = (os.getenv("ILLUC_REVIEW_AUTHOR") or "").strip()
-        if env:
-            return env
-        # Intentionally not "user" so the UI doesn't map it to the local user display name.
-        return "agent"
+    def new_comment(body: str, explicit_author: Optional[str]) -> dict[str, Any]:
+        # Comments written through this helper come from an agent. illuc sets ILLUC_AGENT_KIND
+        # in the agent's environment; otherwise fall back to the configured author name.
+        agent_kind = (os.getenv("ILLUC_AGENT_KIND") or "").strip().lower()
+        author = (explicit_author or "").strip() or (os.getenv("ILLUC_REVIEW_AUTHOR") or "").strip()
+        if agent_kind not in AGENT_KINDS:
+            agent_kind = author.lower() if author.lower() in AGENT_KINDS else ""
+        return {
+            "id": str(uuid.uuid4()),
+            "body": body,
+            # Intentionally not "user" so the UI doesn't map it to the local user display name.
+            "author": author or agent_kind or "agent",
+            "authorKind": "agent",
+            "agentKind": agent_kind or None,
+            "createdAt": iso_utc_now(),
+        }
 
     tasks = store.get("tasks") or {}
     entry = tasks.get(task_id)
 
     if ns.cmd == "add":
-        file_path = str(ns.file).strip()
-        if not file_path:
-            raise RuntimeError("--file cannot be empty")
-        line_old = ns.line_old
-        line_new = ns.line_new
-        if line_old is None and line_new is None:
+        scope = str(ns.scope)
+        file_path = "" if scope == "task" else str(ns.file or "").strip()
+        if scope != "task" and not file_path:
+            raise RuntimeError("--file is required unless --scope task")
+        line_old = ns.line_old if scope == "line" else None
+        line_new = ns.line_new if scope == "line" else None
+        if scope == "line" and line_old is None and line_new is None:
             raise RuntimeError("Review thread must include --line-new or --line-old.")
+        line_range = single_line_range(line_old, line_new) or {}
+        if ns.range_start is not None and scope == "line":
+            if ns.range_start < 1 or ns.range_start > line_range["end"]:
+                raise RuntimeError("--range-start must be between 1 and the thread's line.")
+            line_range["start"] = ns.range_start
         msg = str(ns.message).strip()
         if not msg:
             raise RuntimeError("--message cannot be empty")
-        author = resolve_author(ns.author)
 
         if not isinstance(entry, dict):
             entry = {"taskId": task_id, "threads": []}
@@ -386,14 +736,16 @@ def main(argv: list[str]) -> int:
             threads_raw = []
             entry["threads"] = threads_raw
 
+        # File- and task-level remarks always start a new thread; reply with `comment`.
         selected: Optional[Thread] = None
-        for raw in threads_raw:
+        for raw in threads_raw if scope == "line" else []:
             try:
                 t = normalize_thread(raw)
             except Exception:
                 continue
             if (
-                t.file_path == file_path
+                t.scope == "line"
+                and t.file_path == file_path
                 and t.line_number_old == line_old
                 and t.line_number_new == line_new
             ):
@@ -402,32 +754,39 @@ def main(argv: list[str]) -> int:
 
         if selected is None:
             selected = Thread(
+                id=str(uuid.uuid4()),
+                scope=scope,
                 file_path=file_path,
                 line_number_old=line_old,
                 line_number_new=line_new,
                 line_type=str(ns.line_type),
                 status=str(ns.status),
                 comments=[],
+                range=line_range,
             )
+            anchor = anchor_from_file(worktree_root, file_path, line_range) if line_range else None
             threads_raw.append(
                 {
+                    "id": selected.id,
+                    "scope": selected.scope,
                     "filePath": selected.file_path,
                     "lineNumberOld": selected.line_number_old,
                     "lineNumberNew": selected.line_number_new,
                     "lineType": selected.line_type,
+                    "range": selected.range or None,
                     "status": selected.status,
                     "comments": selected.comments,
+                    "anchor": anchor,
+                    "outdated": False,
                 }
             )
 
-        selected.comments.append(
-            {
-                "id": str(uuid.uuid4()),
-                "body": msg,
-                "author": author,
-                "createdAt": iso_utc_now(),
-            }
-        )
+        comment = new_comment(msg, ns.author)
+        if ns.suggestion is not None:
+            if selected.scope != "line" or selected.range.get("side") != "new":
+                raise RuntimeError("--suggestion needs a line thread on the new side (--line-new).")
+            comment["suggestion"] = {"replacement": ns.suggestion, "appliedAt": None}
+        selected.comments.append(comment)
         map_back_and_write(store_path, store, task_id, selected)
         print(f"add OK task={task_id} key={thread_key(selected)} store={store_path}")
         return 0
@@ -438,19 +797,14 @@ def main(argv: list[str]) -> int:
     thread = select_thread(entry, ns.thread)
     key = thread_key(thread)
 
+    if ns.cmd == "show":
+        return cmd_show(worktree_root, thread, ns.thread)
+
     if ns.cmd == "comment":
         msg = str(ns.message).strip()
         if not msg:
             raise RuntimeError("--message cannot be empty")
-        author = resolve_author(ns.author)
-        thread.comments.append(
-            {
-                "id": str(uuid.uuid4()),
-                "body": msg,
-                "author": author,
-                "createdAt": iso_utc_now(),
-            }
-        )
+        thread.comments.append(new_comment(msg, ns.author))
         map_back_and_write(store_path, store, task_id, thread)
         print(f"comment OK task={task_id} thread=#{ns.thread} key={key} store={store_path}")
         return 0
@@ -459,16 +813,8 @@ def main(argv: list[str]) -> int:
         msg = str(ns.message).strip()
         if not msg:
             raise RuntimeError("--message cannot be empty")
-        author = resolve_author(ns.author)
         thread.status = "resolved"
-        thread.comments.append(
-            {
-                "id": str(uuid.uuid4()),
-                "body": msg,
-                "author": author,
-                "createdAt": iso_utc_now(),
-            }
-        )
+        thread.comments.append(new_comment(msg, ns.author))
         map_back_and_write(store_path, store, task_id, thread)
         print(f"resolve OK task={task_id} thread=#{ns.thread} key={key} store={store_path}")
         return 0
@@ -0,0 +1,3 @@
+pub mod skills_list;
+pub mod skills_reinstall;
+pub mod skills_uninstall;
@@ -0,0 +1,9 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::review::skills::{list_skills, SkillStatus};
+
+pub type Response = Vec<SkillStatus>;
+
+#[tauri::command]
+pub async fn skills_list(app_handle: tauri::AppHandle) -> CommandResult<Response> {
+    list_skills(&app_handle).map_err(|err| format!("{err:#}"))
+}
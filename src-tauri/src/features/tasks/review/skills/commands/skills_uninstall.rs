@@ -0,0 +1,26 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::review::skills::{list_skills, uninstall_skills, SkillStatus};
+use crate::features::tasks::AgentKind;
+use serde::Deserialize;
+
+/// Limits the command to one skill and/or agent; everything when both are omitted.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub agent_kind: Option<AgentKind>,
+}
+
+pub type Response = Vec<SkillStatus>;
+
+#[tauri::command]
+pub async fn skills_uninstall(
+    app_handle: tauri::AppHandle,
+    req: Request,
+) -> CommandResult<Response> {
+    uninstall_skills(&app_handle, req.name.as_deref(), req.agent_kind)
+        .and_then(|_| list_skills(&app_handle))
+        .map_err(|err| format!("{err:#}"))
+}
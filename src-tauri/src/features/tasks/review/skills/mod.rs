@@ -1,5 +1,12 @@
+pub mod commands;
+
+use crate::features::settings::{load_settings_section, user_skills_dir};
+use crate::features::tasks::AgentKind;
+use crate::utils::fs::write_replacing;
 use anyhow::Context;
-use log::info;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 struct SkillAsset {
@@ -7,8 +14,22 @@ struct SkillAsset {
     body: &'static str, // Entire SKILL.md contents.
 }
 
-const REVIEW_HELPER_NAME: &str = "illuc-review.py";
-const REVIEW_HELPER_BODY: &str = include_str!("assets/illuc-review.py");
+/// Name the skills call the review helper by.
+const REVIEW_CLI_NAME: &str = "illuc-cli";
+/// Helper installed by older versions, removed in favor of the CLI.
+const LEGACY_HELPER_NAME: &str = "illuc-review.py";
+/// Stand-in for the CLI when the binary is not shipped next to the app, and on Windows, where
+/// the agents run in WSL (it accepts the same `review ...` commands).
+const REVIEW_HELPER_FALLBACK: &str = include_str!("assets/illuc-review.py");
+/// Records which illuc version installed a skill and the hash of every file it wrote.
+const MARKER_NAME: &str = ".illuc-skill.json";
+/// Replaced with the skill's install dir in SKILL.md, so it can point at its own helper.
+const SKILL_DIR_PLACEHOLDER: &str = "{{SKILL_DIR}}";
+const SKILL_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Per-repo skill packs, under the repo's `.illuc` dir.
+const REPO_SKILLS_DIR: &str = ".illuc/skills";
+/// Keeps skills installed into a worktree out of git, like the `.illuc` dir itself.
+const WORKTREE_SKILL_GITIGNORE: &str = "*\n";
 
 // Embed repo skill files so this also works in packaged builds.
 const SKILLS: &[SkillAsset] = &[
@@ -22,6 +43,89 @@ const SKILLS: &[SkillAsset] = &[
     },
 ];
 
+/// `[skills.dirs]`: where each agent looks for skills. A missing key keeps the agent's default
+/// location; an empty list installs nothing for that agent.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SkillDirsSettings {
+    pub codex: Option<Vec<String>>,
+    pub copilot: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SkillMarker {
+    version: String,
+    /// Blob id of each installed file, by file name.
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkillState {
+    NotInstalled,
+    /// Installed by this version of illuc and unchanged.
+    Current,
+    /// Installed by an older illuc, or its files differ from the bundled ones.
+    Outdated,
+    /// Edited since illuc installed it; left alone until reinstalled.
+    Modified,
+    /// A skill of the same name that illuc did not install.
+    Unmanaged,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillStatus {
+    pub name: String,
+    pub agent_kind: AgentKind,
+    pub dir: String,
+    pub state: SkillState,
+    pub installed_version: Option<String>,
+}
+
+/// One directory an agent loads skills from.
+struct SkillTarget {
+    agent_kind: AgentKind,
+    root: PathBuf,
+}
+
+/// Where a skill's files come from.
+enum SkillSource {
+    Builtin(&'static SkillAsset),
+    /// A folder holding a SKILL.md and any helper files it uses.
+    Pack {
+        name: String,
+        dir: PathBuf,
+    },
+}
+
+impl SkillSource {
+    fn name(&self) -> &str {
+        match self {
+            SkillSource::Builtin(skill) => skill.name,
+            SkillSource::Pack { name, .. } => name,
+        }
+    }
+}
+
+enum FileSource {
+    Bytes(Vec<u8>),
+    Copy(PathBuf),
+}
+
+struct SkillFile {
+    /// Path inside the skill dir, `/`-separated.
+    name: String,
+    source: FileSource,
+    executable: bool,
+}
+
+/// The files illuc writes into one skill dir.
+struct SkillFiles {
+    files: Vec<SkillFile>,
+}
+
 fn resolve_home_dir() -> anyhow::Result<PathBuf> {
     std::env::var_os("HOME")
         .or_else(|| std::env::var_os("USERPROFILE"))
@@ -29,60 +133,529 @@ fn resolve_home_dir() -> anyhow::Result<PathBuf> {
         .context("failed to resolve home directory")
 }
 
-fn write_skill(root: &Path, skill: &SkillAsset) -> anyhow::Result<()> {
-    let dir = root.join(skill.name);
-    std::fs::create_dir_all(&dir)
+fn expand_home(path: &str, home: &Path) -> PathBuf {
+    match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(rest) => home.join(rest),
+        None if path == "~" => home.to_path_buf(),
+        None => PathBuf::from(path),
+    }
+}
+
+fn skill_targets(app: &tauri::AppHandle) -> anyhow::Result<Vec<SkillTarget>> {
+    let home = resolve_home_dir()?;
+    let settings: SkillDirsSettings = load_settings_section(app, "skills.dirs");
+    let defaults = [
+        (
+            AgentKind::Codex,
+            settings.codex,
+            home.join(".agents").join("skills"),
+        ),
+        (
+            AgentKind::Copilot,
+            settings.copilot,
+            home.join(".copilot").join("skills"),
+        ),
+    ];
+    let mut targets = Vec::new();
+    for (agent_kind, configured, default_root) in defaults {
+        let roots = match configured {
+            Some(dirs) => dirs
+                .iter()
+                .map(|dir| dir.trim())
+                .filter(|dir| !dir.is_empty())
+                .map(|dir| expand_home(dir, &home))
+                .collect(),
+            None => vec![default_root],
+        };
+        targets.extend(
+            roots
+                .into_iter()
+                .map(|root| SkillTarget { agent_kind, root }),
+        );
+    }
+    Ok(targets)
+}
+
+/// Where an agent picks up skills that belong to the project it runs in.
+fn project_skills_dir(agent_kind: AgentKind) -> &'static str {
+    match agent_kind {
+        AgentKind::Codex => ".agents/skills",
+        AgentKind::Copilot => ".github/skills",
+    }
+}
+
+/// Skill folders (those with a SKILL.md) directly under `root`.
+fn read_skill_packs(root: &Path) -> Vec<SkillSource> {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut packs: Vec<SkillSource> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|dir| dir.join("SKILL.md").is_file())
+        .filter_map(|dir| {
+            let name = dir.file_name()?.to_str()?.to_string();
+            Some(SkillSource::Pack { name, dir })
+        })
+        .collect();
+    packs.sort_by(|a, b| a.name().cmp(b.name()));
+    packs
+}
+
+/// The built-in skills followed by the user's packs from `<config-dir>/skills`.
+fn skill_sources(app: &tauri::AppHandle) -> Vec<SkillSource> {
+    let mut sources: Vec<SkillSource> = SKILLS.iter().map(SkillSource::Builtin).collect();
+    let packs = match user_skills_dir(app) {
+        Ok(root) => read_skill_packs(&root),
+        Err(err) => {
+            warn!("failed to resolve the user skills dir: {err:#}");
+            Vec::new()
+        }
+    };
+    for pack in packs {
+        if SKILLS.iter().any(|skill| skill.name == pack.name()) {
+            warn!(
+                "skipping skill pack {}: the name is taken by a built-in skill",
+                pack.name()
+            );
+            continue;
+        }
+        sources.push(pack);
+    }
+    sources
+}
+
+/// The `illuc-cli` sidecar shipped next to the app, if present (Tauri strips the target triple
+/// from sidecar names when it bundles them).
+#[cfg(not(target_os = "windows"))]
+fn bundled_cli_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let cli = exe
+        .parent()?
+        .join(format!("{REVIEW_CLI_NAME}{}", std::env::consts::EXE_SUFFIX));
+    cli.is_file().then_some(cli)
+}
+
+/// On Windows the agents run inside WSL, which cannot execute the shipped `illuc-cli.exe`, so
+/// the skills always get the script helper there.
+#[cfg(target_os = "windows")]
+fn bundled_cli_path() -> Option<PathBuf> {
+    None
+}
+
+fn blob_id_of_bytes(bytes: &[u8]) -> anyhow::Result<String> {
+    Ok(git2::Oid::hash_object(git2::ObjectType::Blob, bytes)?.to_string())
+}
+
+fn blob_id_of_file(path: &Path) -> Option<String> {
+    git2::Oid::hash_file(git2::ObjectType::Blob, path)
+        .ok()
+        .map(|oid| oid.to_string())
+}
+
+fn render_skill_md(body: &str, dir: &Path) -> Vec<u8> {
+    body.replace(SKILL_DIR_PLACEHOLDER, &dir.to_string_lossy())
+        .into_bytes()
+}
+
+/// Every file under `root`, as `/`-separated paths relative to `root`.
+fn collect_pack_files(root: &Path, prefix: &str, out: &mut Vec<String>) -> anyhow::Result<()> {
+    let entries =
+        std::fs::read_dir(root).with_context(|| format!("failed to read {}", root.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = format!("{prefix}{name}");
+        if entry.file_type()?.is_dir() {
+            collect_pack_files(&entry.path(), &format!("{relative}/"), out)?;
+        } else if relative != MARKER_NAME {
+            out.push(relative);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .map(|meta| meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+impl SkillFiles {
+    /// The files `source` installs into `dir`; SKILL.md gets `{{SKILL_DIR}}` filled in.
+    fn new(source: &SkillSource, dir: &Path, cli: Option<&Path>) -> anyhow::Result<Self> {
+        let files = match source {
+            SkillSource::Builtin(skill) => vec![
+                SkillFile {
+                    name: "SKILL.md".to_string(),
+                    source: FileSource::Bytes(render_skill_md(skill.body, dir)),
+                    executable: false,
+                },
+                SkillFile {
+                    name: REVIEW_CLI_NAME.to_string(),
+                    source: match cli {
+                        Some(cli) => FileSource::Copy(cli.to_path_buf()),
+                        None => FileSource::Bytes(REVIEW_HELPER_FALLBACK.as_bytes().to_vec()),
+                    },
+                    executable: true,
+                },
+            ],
+            SkillSource::Pack { dir: pack_dir, .. } => {
+                let mut names = Vec::new();
+                collect_pack_files(pack_dir, "", &mut names)?;
+                names.sort();
+                let mut files = Vec::new();
+                for name in names {
+                    let path = pack_dir.join(&name);
+                    let source = if name == "SKILL.md" {
+                        let body = std::fs::read_to_string(&path)
+                            .with_context(|| format!("failed to read {}", path.display()))?;
+                        FileSource::Bytes(render_skill_md(&body, dir))
+                    } else {
+                        FileSource::Copy(path.clone())
+                    };
+                    files.push(SkillFile {
+                        executable: is_executable(&path),
+                        name,
+                        source,
+                    });
+                }
+                files
+            }
+        };
+        Ok(Self { files })
+    }
+
+    fn with_file(mut self, name: &str, contents: &str) -> Self {
+        self.files.push(SkillFile {
+            name: name.to_string(),
+            source: FileSource::Bytes(contents.as_bytes().to_vec()),
+            executable: false,
+        });
+        self
+    }
+
+    /// Blob ids of the files as illuc would install them.
+    fn blob_ids(&self) -> anyhow::Result<BTreeMap<String, String>> {
+        self.files
+            .iter()
+            .map(|file| {
+                let blob_id = match &file.source {
+                    FileSource::Bytes(bytes) => blob_id_of_bytes(bytes)?,
+                    FileSource::Copy(path) => blob_id_of_file(path)
+                        .with_context(|| format!("failed to hash {}", path.display()))?,
+                };
+                Ok((file.name.clone(), blob_id))
+            })
+            .collect()
+    }
+}
+
+fn read_marker(dir: &Path) -> Option<SkillMarker> {
+    let contents = std::fs::read_to_string(dir.join(MARKER_NAME)).ok()?;
+    match serde_json::from_str(&contents) {
+        Ok(marker) => Some(marker),
+        Err(err) => {
+            warn!(
+                "ignoring unreadable skill marker in {}: {}",
+                dir.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+fn skill_state(
+    dir: &Path,
+    files: &SkillFiles,
+) -> anyhow::Result<(SkillState, Option<SkillMarker>)> {
+    if !dir.join("SKILL.md").exists() {
+        return Ok((SkillState::NotInstalled, None));
+    }
+    let Some(marker) = read_marker(dir) else {
+        // Versions before the marker always overwrote their files and shipped the script helper.
+        let state = if dir.join(LEGACY_HELPER_NAME).exists() {
+            SkillState::Outdated
+        } else {
+            SkillState::Unmanaged
+        };
+        return Ok((state, None));
+    };
+    let edited = marker
+        .files
+        .iter()
+        .any(|(name, blob_id)| blob_id_of_file(&dir.join(name)).as_ref() != Some(blob_id));
+    let state = if edited {
+        SkillState::Modified
+    } else if marker.version != SKILL_VERSION || marker.files != files.blob_ids()? {
+        SkillState::Outdated
+    } else {
+        SkillState::Current
+    };
+    Ok((state, Some(marker)))
+}
+
+fn remove_file_if_exists(path: &Path) -> anyhow::Result<()> {
+    if path.exists() {
+        std::fs::remove_file(path)
+            .with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Removes `dir` and its subdirs, bottom-up, as long as they are empty.
+fn remove_empty_dirs(dir: &Path) -> anyhow::Result<()> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(());
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+    let is_empty = std::fs::read_dir(dir)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+    if is_empty {
+        std::fs::remove_dir(dir).with_context(|| format!("failed to remove {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+fn write_skill(dir: &Path, files: &SkillFiles) -> anyhow::Result<()> {
+    std::fs::create_dir_all(dir)
         .with_context(|| format!("failed to create skills dir {}", dir.display()))?;
-    let skill_path = dir.join("SKILL.md");
-    let helper_path = dir.join(REVIEW_HELPER_NAME);
-
-    // Always overwrite with the latest version on startup.
-    std::fs::write(&skill_path, skill.body.as_bytes())
-        .with_context(|| format!("failed to write {}", skill_path.display()))?;
-    std::fs::write(&helper_path, REVIEW_HELPER_BODY.as_bytes())
-        .with_context(|| format!("failed to write {}", helper_path.display()))?;
-
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&helper_path)
-            .with_context(|| format!("failed to stat {}", helper_path.display()))?
-            .permissions();
-        perms.set_mode(0o755);
-        std::fs::set_permissions(&helper_path, perms)
-            .with_context(|| format!("failed to chmod {}", helper_path.display()))?;
+
+    // Files a previous install wrote that this one no longer ships.
+    let blob_ids = files.blob_ids()?;
+    if let Some(previous) = read_marker(dir) {
+        for name in previous
+            .files
+            .keys()
+            .filter(|name| !blob_ids.contains_key(*name))
+        {
+            remove_file_if_exists(&dir.join(name))?;
+        }
+    }
+    remove_file_if_exists(&dir.join(LEGACY_HELPER_NAME))?;
+
+    for file in &files.files {
+        let path = dir.join(&file.name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        match &file.source {
+            FileSource::Bytes(bytes) => std::fs::write(&path, bytes)
+                .with_context(|| format!("failed to write {}", path.display()))?,
+            FileSource::Copy(from) => {
+                std::fs::copy(from, &path).with_context(|| {
+                    format!("failed to copy {} to {}", from.display(), path.display())
+                })?;
+            }
+        }
+
+        #[cfg(unix)]
+        if file.executable {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&path)
+                .with_context(|| format!("failed to stat {}", path.display()))?
+                .permissions();
+            perms.set_mode(0o755);
+            std::fs::set_permissions(&path, perms)
+                .with_context(|| format!("failed to chmod {}", path.display()))?;
+        }
     }
 
+    let marker = SkillMarker {
+        version: SKILL_VERSION.to_string(),
+        files: blob_ids,
+    };
+    let marker_path = dir.join(MARKER_NAME);
+    write_replacing(
+        &marker_path,
+        serde_json::to_string_pretty(&marker)?.as_bytes(),
+    )
+    .with_context(|| format!("failed to write {}", marker_path.display()))?;
     Ok(())
 }
 
-fn install_to(root: &Path) -> anyhow::Result<()> {
-    for skill in SKILLS {
-        write_skill(root, skill)?;
+/// Removes the files illuc installed; dirs only go once nothing else is left in them.
+fn remove_skill(dir: &Path) -> anyhow::Result<()> {
+    let installed: Vec<String> = match read_marker(dir) {
+        Some(marker) => marker.files.into_keys().collect(),
+        None => vec!["SKILL.md".to_string(), REVIEW_CLI_NAME.to_string()],
+    };
+    for name in installed {
+        remove_file_if_exists(&dir.join(name))?;
+    }
+    remove_file_if_exists(&dir.join(LEGACY_HELPER_NAME))?;
+    remove_file_if_exists(&dir.join(MARKER_NAME))?;
+    remove_empty_dirs(dir)
+}
+
+/// Installs `files` unless the dir is current, edited locally, or not illuc's.
+fn install_if_needed(dir: &Path, files: &SkillFiles) -> anyhow::Result<bool> {
+    match skill_state(dir, files)?.0 {
+        SkillState::NotInstalled | SkillState::Outdated => {
+            write_skill(dir, files)?;
+            Ok(true)
+        }
+        SkillState::Current => Ok(false),
+        SkillState::Modified => {
+            warn!(
+                "skill {} has local edits; reinstall it from illuc to update it",
+                dir.display()
+            );
+            Ok(false)
+        }
+        SkillState::Unmanaged => {
+            warn!(
+                "skill {} was not installed by illuc; leaving it in place",
+                dir.display()
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Runs `visit` for every skill in every target dir, optionally limited to one skill and agent.
+fn for_each_skill<F>(
+    app: &tauri::AppHandle,
+    name: Option<&str>,
+    agent_kind: Option<AgentKind>,
+    mut visit: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(&SkillSource, &SkillTarget, &Path, &SkillFiles) -> anyhow::Result<()>,
+{
+    let sources = skill_sources(app);
+    if let Some(name) = name {
+        anyhow::ensure!(
+            sources.iter().any(|source| source.name() == name),
+            "unknown skill: {name}"
+        );
+    }
+    let cli = bundled_cli_path();
+    if cli.is_none() && cfg!(not(target_os = "windows")) {
+        warn!("{REVIEW_CLI_NAME} not found next to the app; using the script helper instead");
+    }
+    for target in skill_targets(app)? {
+        if agent_kind.is_some_and(|kind| kind != target.agent_kind) {
+            continue;
+        }
+        for source in &sources {
+            if name.is_some_and(|name| name != source.name()) {
+                continue;
+            }
+            let dir = target.root.join(source.name());
+            let files = SkillFiles::new(source, &dir, cli.as_deref())?;
+            visit(source, &target, &dir, &files)?;
+        }
     }
     Ok(())
 }
 
-/// Install illuc's built-in skills into external tool locations.
+/// State of every illuc skill in every configured skills dir.
+pub fn list_skills(app: &tauri::AppHandle) -> anyhow::Result<Vec<SkillStatus>> {
+    let mut statuses = Vec::new();
+    for_each_skill(app, None, None, |source, target, dir, files| {
+        let (state, marker) = skill_state(dir, files)?;
+        statuses.push(SkillStatus {
+            name: source.name().to_string(),
+            agent_kind: target.agent_kind,
+            dir: dir.to_string_lossy().to_string(),
+            state,
+            installed_version: marker.map(|marker| marker.version),
+        });
+        Ok(())
+    })?;
+    Ok(statuses)
+}
+
+/// Installs the skills over whatever is there, local edits included.
+pub fn reinstall_skills(
+    app: &tauri::AppHandle,
+    name: Option<&str>,
+    agent_kind: Option<AgentKind>,
+) -> anyhow::Result<()> {
+    for_each_skill(app, name, agent_kind, |_, _, dir, files| {
+        write_skill(dir, files)
+    })
+}
+
+/// Removes illuc's skills; skills of the same name that illuc did not install are kept.
+pub fn uninstall_skills(
+    app: &tauri::AppHandle,
+    name: Option<&str>,
+    agent_kind: Option<AgentKind>,
+) -> anyhow::Result<()> {
+    for_each_skill(
+        app,
+        name,
+        agent_kind,
+        |_, _, dir, files| match skill_state(dir, files)?.0 {
+            SkillState::NotInstalled => Ok(()),
+            SkillState::Unmanaged => {
+                warn!(
+                    "not removing {}: it was not installed by illuc",
+                    dir.display()
+                );
+                Ok(())
+            }
+            _ => remove_skill(dir),
+        },
+    )
+}
+
+/// Install illuc's built-in skills and the user's skill packs (`<config-dir>/skills/<skill>/`)
+/// into each agent's skills dirs (`[skills.dirs]` in the settings).
 ///
-/// Target dirs:
+/// Default dirs:
 /// - `~/.agents/skills/<skill>/SKILL.md` (Codex/Agents)
 /// - `~/.copilot/skills/<skill>/SKILL.md` (Copilot CLI)
-pub fn install_predefined_skills_on_startup() -> anyhow::Result<()> {
-    let home = resolve_home_dir()?;
-
-    let agents_dir = home.join(".agents").join("skills");
-    let copilot_dir = home.join(".copilot").join("skills");
-
-    install_to(&agents_dir)
-        .with_context(|| format!("failed installing skills into {}", agents_dir.display()))?;
-    install_to(&copilot_dir)
-        .with_context(|| format!("failed installing skills into {}", copilot_dir.display()))?;
+///
+/// Each built-in skill dir also gets the `illuc-cli` review helper the skills call. Skills that
+/// are missing or outdated are (re)installed; locally edited ones are left alone.
+pub fn install_predefined_skills_on_startup(app: &tauri::AppHandle) -> anyhow::Result<()> {
+    let mut installed = 0;
+    for_each_skill(app, None, None, |_, _, dir, files| {
+        if install_if_needed(dir, files)? {
+            installed += 1;
+        }
+        Ok(())
+    })?;
+    info!("installed or updated {installed} skill(s)");
+    Ok(())
+}
 
-    info!(
-        "installed predefined skills into {} and {}",
-        agents_dir.display(),
-        copilot_dir.display()
-    );
+/// Installs the repo's skill packs (`<repo>/.illuc/skills/<skill>/`) into the task's worktree,
+/// where `agent_kind` looks for project skills. They stay out of git.
+pub fn install_repo_skills(
+    base_repo_path: &Path,
+    worktree_path: &Path,
+    agent_kind: AgentKind,
+) -> anyhow::Result<()> {
+    let target_root = worktree_path.join(project_skills_dir(agent_kind));
+    for pack in read_skill_packs(&base_repo_path.join(REPO_SKILLS_DIR)) {
+        let dir = target_root.join(pack.name());
+        let files =
+            SkillFiles::new(&pack, &dir, None)?.with_file(".gitignore", WORKTREE_SKILL_GITIGNORE);
+        if install_if_needed(&dir, &files)? {
+            info!(
+                "installed repo skill {} into {}",
+                pack.name(),
+                dir.display()
+            );
+        }
+    }
     Ok(())
 }
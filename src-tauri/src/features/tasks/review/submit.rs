@@ -0,0 +1,51 @@
+use super::{ReviewScope, ReviewSide, ReviewThread};
+
+fn thread_location(thread: &ReviewThread) -> String {
+    match thread.scope {
+        ReviewScope::Task => "The change as a whole".to_string(),
+        ReviewScope::File => format!("{} (whole file)", thread.file_path),
+        ReviewScope::Line => {
+            let lines = match thread.range {
+                Some(range) if range.is_multi_line() => {
+                    format!("lines {}-{}", range.start, range.end)
+                }
+                Some(range) => format!("line {}", range.end),
+                None => "line ?".to_string(),
+            };
+            let removed = thread
+                .range
+                .is_some_and(|range| range.side == ReviewSide::Old);
+            format!(
+                "{} {}{}{}",
+                thread.file_path,
+                lines,
+                if removed { " (removed code)" } else { "" },
+                if thread.outdated { " (outdated)" } else { "" }
+            )
+        }
+    }
+}
+
+/// Builds the instruction that hands the given threads to the task's agent.
+pub fn build_prompt(threads: &[ReviewThread]) -> String {
+    let mut prompt = String::from(
+        "Please address the open review feedback on this task using the illuc-fix-review \
+         skill. For each thread below: make the change (or explain why not), reply on the \
+         thread and resolve it with `illuc-cli`.\n",
+    );
+    for (index, thread) in threads.iter().enumerate() {
+        prompt.push_str(&format!("\n{}. {}\n", index + 1, thread_location(thread)));
+        for comment in &thread.comments {
+            let body = comment.body.trim().replace('\n', "\n     ");
+            prompt.push_str(&format!("   - {}: {}\n", comment.author, body));
+            if comment
+                .suggestion
+                .as_ref()
+                .is_some_and(|suggestion| suggestion.applied_at.is_none())
+            {
+                prompt.push_str("     (includes a suggested replacement; `show` prints it)\n");
+            }
+        }
+    }
+    prompt
+}
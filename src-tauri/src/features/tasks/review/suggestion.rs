@@ -0,0 +1,164 @@
+use super::anchor::anchor_from_file;
+use super::{ReviewCommentStatus, ReviewRange, ReviewScope, ReviewSide, ReviewThread};
+use crate::error::{Result, TaskError};
+use chrono::Utc;
+use std::path::Path;
+
+fn strip_line_ending(line: &str) -> &str {
+    line.trim_end_matches('\n').trim_end_matches('\r')
+}
+
+/// Whether suggestions can be attached to the thread: only line threads on the worktree side.
+pub fn accepts_suggestion(thread: &ReviewThread) -> bool {
+    thread.scope == ReviewScope::Line
+        && thread
+            .range
+            .is_some_and(|range| range.side == ReviewSide::New)
+}
+
+/// Replaces lines `start..=end` (1-based) of `contents` with `replacement`, keeping the
+/// file's line endings. Fails when those lines no longer read `expected`.
+fn replace_lines(
+    contents: &str,
+    range: ReviewRange,
+    expected: &[String],
+    replacement: &str,
+) -> Result<String> {
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let start = (range.start as usize).saturating_sub(1);
+    let end = range.end as usize;
+    let current = lines.get(start..end).unwrap_or_default();
+    let matches = current.len() == expected.len()
+        && current
+            .iter()
+            .zip(expected)
+            .all(|(line, expected)| strip_line_ending(line) == strip_line_ending(expected));
+    if current.is_empty() || !matches {
+        return Err(TaskError::Message(
+            "The suggested lines changed since the suggestion was made.".to_string(),
+        ));
+    }
+
+    let eol = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let keeps_final_newline = current.last().is_some_and(|line| line.ends_with('\n'));
+    let replacement_lines: Vec<&str> = replacement.lines().collect();
+    let mut output = lines[..start].concat();
+    for (index, line) in replacement_lines.iter().enumerate() {
+        output.push_str(line);
+        if index + 1 < replacement_lines.len() || keeps_final_newline {
+            output.push_str(eol);
+        }
+    }
+    output.push_str(&lines[end..].concat());
+    Ok(output)
+}
+
+/// Writes the comment's suggestion into the worktree file, marks it applied and resolves
+/// the thread. The thread is re-anchored to the replacement, or marked outdated when the
+/// suggestion deleted its lines.
+pub fn apply_suggestion(
+    worktree_root: &Path,
+    thread: &mut ReviewThread,
+    comment_id: &str,
+) -> Result<()> {
+    let range = match thread.range {
+        Some(range) if accepts_suggestion(thread) => range,
+        _ => {
+            return Err(TaskError::Message(
+                "Suggestions can only be applied to worktree lines.".to_string(),
+            ))
+        }
+    };
+    if thread.outdated {
+        return Err(TaskError::Message(
+            "The thread is outdated; its lines no longer exist.".to_string(),
+        ));
+    }
+    let anchor = thread.anchor.as_ref().ok_or_else(|| {
+        TaskError::Message("The thread has no recorded line content to verify.".to_string())
+    })?;
+    let expected = if anchor.lines.is_empty() {
+        vec![anchor.line.clone()]
+    } else {
+        anchor.lines.clone()
+    };
+    let comment = thread
+        .comments
+        .iter()
+        .find(|comment| comment.id == comment_id)
+        .ok_or_else(|| TaskError::Message("Review comment not found.".to_string()))?;
+    let suggestion = comment
+        .suggestion
+        .as_ref()
+        .ok_or_else(|| TaskError::Message("Review comment has no suggestion.".to_string()))?;
+    if suggestion.applied_at.is_some() {
+        return Err(TaskError::Message(
+            "The suggestion was already applied.".to_string(),
+        ));
+    }
+
+    let file_path = worktree_root.join(&thread.file_path);
+    let contents = std::fs::read_to_string(&file_path)?;
+    let patched = replace_lines(&contents, range, &expected, &suggestion.replacement)?;
+    std::fs::write(&file_path, patched)?;
+
+    let replaced = suggestion.replacement.lines().count() as u32;
+    if let Some(suggestion) = thread
+        .comments
+        .iter_mut()
+        .find(|comment| comment.id == comment_id)
+        .and_then(|comment| comment.suggestion.as_mut())
+    {
+        suggestion.applied_at = Some(Utc::now());
+    }
+    if replaced == 0 {
+        thread.outdated = true;
+    } else {
+        let range = ReviewRange {
+            side: ReviewSide::New,
+            start: range.start,
+            end: range.start + replaced - 1,
+        };
+        thread.range = Some(range);
+        thread.line_number_new = Some(range.end);
+        thread.anchor = anchor_from_file(worktree_root, &thread.file_path, range);
+    }
+    thread.status = ReviewCommentStatus::Resolved;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::replace_lines;
+    use crate::error::Result;
+    use crate::features::tasks::review::{ReviewRange, ReviewSide};
+
+    fn range(start: u32, end: u32) -> ReviewRange {
+        ReviewRange {
+            side: ReviewSide::New,
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn replace_lines_keeps_line_endings_and_rejects_changed_lines() -> Result<()> {
+        let expected = vec!["b".to_string(), "c".to_string()];
+        let patched = replace_lines("a\r\nb\r\nc\r\nd", range(2, 3), &expected, "x\ny\nz")?;
+        assert_eq!(patched, "a\r\nx\r\ny\r\nz\r\nd");
+
+        let patched = replace_lines("a\nb\nc", range(2, 3), &expected, "x")?;
+        assert_eq!(patched, "a\nx");
+
+        let patched = replace_lines("a\nb\nc\n", range(2, 3), &expected, "")?;
+        assert_eq!(patched, "a\n");
+
+        assert!(replace_lines("a\nb\nq\n", range(2, 3), &expected, "x").is_err());
+        assert!(replace_lines("a\nb\n", range(2, 3), &expected, "x").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Review verdicts live in the base repository's `.illuc`, not in the task's worktree, so the
+//! task's agent cannot approve its own work by editing `local-review.json`.
+
+use super::{ReviewStore, ReviewVerdict};
+use crate::error::{Result, TaskError};
+use crate::utils::fs::ensure_illuc_dir;
+use crate::utils::json_store::{self, StoreSchema};
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+const VERDICTS_VERSION: u32 = 1;
+const VERDICTS_FILE: &str = "review-verdicts.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerdictStore {
+    pub version: u32,
+    pub tasks: HashMap<String, TaskVerdict>,
+}
+
+impl Default for VerdictStore {
+    fn default() -> Self {
+        Self {
+            version: VERDICTS_VERSION,
+            tasks: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskVerdict {
+    pub verdict: ReviewVerdict,
+    pub verdict_by: Option<String>,
+    pub verdict_at: Option<DateTime<Utc>>,
+}
+
+const VERDICTS_SCHEMA: StoreSchema = StoreSchema {
+    version: VERDICTS_VERSION,
+    migrations: &[],
+};
+
+/// The verdict store of the repository `worktree_root` is a checkout of.
+fn verdicts_path(worktree_root: &Path) -> Result<PathBuf> {
+    let repo = Repository::open(worktree_root).map_err(anyhow::Error::from)?;
+    let base_repo_root = repo.commondir().parent().ok_or_else(|| {
+        TaskError::Message(format!(
+            "Unable to find the main checkout of {}.",
+            worktree_root.display()
+        ))
+    })?;
+    Ok(ensure_illuc_dir(base_repo_root)?.join(VERDICTS_FILE))
+}
+
+pub fn load_verdicts(worktree_root: &Path) -> Result<VerdictStore> {
+    json_store::load(&verdicts_path(worktree_root)?, &VERDICTS_SCHEMA)
+}
+
+pub fn set_verdict(worktree_root: &Path, task_id: &str, verdict: TaskVerdict) -> Result<()> {
+    json_store::update(
+        &verdicts_path(worktree_root)?,
+        &VERDICTS_SCHEMA,
+        |store: &mut VerdictStore| {
+            store.tasks.insert(task_id.to_string(), verdict);
+            Ok(())
+        },
+    )
+}
+
+/// Replaces whatever verdict the review store holds with the recorded one.
+pub(super) fn apply_verdicts(store: &mut ReviewStore, verdicts: &VerdictStore) {
+    for (task_id, entry) in &mut store.tasks {
+        let verdict = verdicts.tasks.get(task_id).cloned().unwrap_or_default();
+        entry.verdict = verdict.verdict;
+        entry.verdict_by = verdict.verdict_by;
+        entry.verdict_at = verdict.verdict_at;
+    }
+}
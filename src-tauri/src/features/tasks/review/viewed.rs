@@ -0,0 +1,69 @@
+use super::{load_store, ReviewCommentStatus, ReviewVerdict, TaskReviewEntry};
+use crate::error::Result;
+use crate::features::tasks::git::{worktree_blob_id, DiffFile};
+use log::warn;
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewProgress {
+    pub files_total: usize,
+    pub files_viewed: usize,
+    pub threads_total: usize,
+    /// Active or pending threads.
+    pub threads_open: usize,
+    pub threads_resolved: usize,
+    pub verdict: ReviewVerdict,
+}
+
+/// Whether the file's viewed mark still matches its worktree content.
+fn is_viewed(worktree_root: &Path, entry: &TaskReviewEntry, file_path: &str) -> bool {
+    let Some(mark) = entry.viewed_files.get(file_path) else {
+        return false;
+    };
+    match worktree_blob_id(worktree_root, file_path) {
+        Ok(blob_id) => blob_id == mark.blob_id,
+        Err(err) => {
+            warn!("failed to hash {} for viewed state: {}", file_path, err);
+            false
+        }
+    }
+}
+
+/// Paths in `files` that are marked viewed at their current content, plus the review progress
+/// of the task over those files.
+pub fn viewed_state(
+    worktree_root: &Path,
+    task_id: &str,
+    files: &[DiffFile],
+) -> Result<(Vec<String>, ReviewProgress)> {
+    let store = load_store(worktree_root)?;
+    let Some(entry) = store.tasks.get(task_id) else {
+        return Ok((
+            Vec::new(),
+            ReviewProgress {
+                files_total: files.len(),
+                ..ReviewProgress::default()
+            },
+        ));
+    };
+    let viewed_paths: Vec<String> = files
+        .iter()
+        .filter(|file| is_viewed(worktree_root, entry, &file.path))
+        .map(|file| file.path.clone())
+        .collect();
+    let progress = ReviewProgress {
+        files_total: files.len(),
+        files_viewed: viewed_paths.len(),
+        threads_total: entry.threads.len(),
+        threads_open: entry.open_thread_count(),
+        threads_resolved: entry
+            .threads
+            .iter()
+            .filter(|thread| thread.status == ReviewCommentStatus::Resolved)
+            .count(),
+        verdict: entry.verdict,
+    };
+    Ok((viewed_paths, progress))
+}
@@ -1,18 +1,31 @@
 use super::*;
 use crate::error::{Result, TaskError};
-use crate::features::tasks::agents::Agent;
+use crate::features::tasks::agents::{Agent, AgentCallbacks, AgentRuntime};
 use crate::features::tasks::events::{emit_status, emit_terminal_exit, emit_terminal_output};
+use crate::features::tasks::history::{self, RestartAttempt};
+use crate::features::tasks::review::auto_review;
+use crate::features::tasks::review::skills::install_repo_skills;
+use crate::features::time_tracking::{record_interval_logged, ActivitySession, TimeCategory};
 use crate::utils::path::normalize_path_string;
-use crate::utils::pty::{ChildHandle, MasterHandle, WriteHandle};
-use chrono::Utc;
-use log::warn;
+use crate::utils::pty::{ChildHandle, MasterHandle, TerminalSize, WriteHandle};
+use crate::utils::store_writer;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use log::{info, warn};
 use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use tauri::AppHandle;
 use uuid::Uuid;
 
+const PROMPT_SUBMIT_DELAY: Duration = Duration::from_millis(150);
+/// How long a freshly started agent gets to come up before a prompt is typed in anyway.
+const AGENT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
 #[derive(Clone, Default)]
 pub struct TaskManager {
     pub(crate) inner: Arc<TaskManagerInner>,
@@ -21,6 +34,8 @@ pub struct TaskManager {
 pub(crate) struct TaskManagerInner {
     pub(crate) tasks: RwLock<HashMap<Uuid, TaskRecord>>,
     pub(crate) diff_watchers: Mutex<HashMap<Uuid, DiffWatcher>>,
+    /// Open human activity sessions, kept apart from `tasks` so keystrokes need no write lock.
+    pub(crate) activity: Mutex<HashMap<Uuid, ActivitySession>>,
 }
 
 impl Default for TaskManagerInner {
@@ -28,6 +43,7 @@ impl Default for TaskManagerInner {
         Self {
             tasks: RwLock::new(HashMap::new()),
             diff_watchers: Mutex::new(HashMap::new()),
+            activity: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -38,6 +54,45 @@ pub(crate) struct TaskRecord {
     pub(crate) summary: TaskSummary,
     pub(crate) runtime: Option<TaskRuntime>,
     pub(crate) shell: Option<TaskRuntime>,
+    pub(crate) restart_policy: Option<RestartPolicy>,
+    /// Reviewer to start once the task's agent completes.
+    pub(crate) auto_review: Option<AgentKind>,
+    /// Set while a reviewer runs in the agent terminal of a completed task.
+    pub(crate) review_pass: Option<ReviewPass>,
+    pub(crate) terminal_size: Option<TerminalSize>,
+    pub(crate) status_since: DateTime<Utc>,
+}
+
+impl TaskRecord {
+    pub(crate) fn new(summary: TaskSummary) -> Self {
+        Self {
+            agent: build_agent(summary.agent_kind),
+            agent_kind: summary.agent_kind,
+            summary,
+            runtime: None,
+            shell: None,
+            restart_policy: None,
+            auto_review: None,
+            review_pass: None,
+            terminal_size: None,
+            status_since: Utc::now(),
+        }
+    }
+
+    /// The agent drawing the agent terminal: the reviewer during a review pass.
+    pub(crate) fn terminal_agent_mut(&mut self) -> &mut dyn Agent {
+        match &mut self.review_pass {
+            Some(pass) => pass.agent.as_mut(),
+            None => self.agent.as_mut(),
+        }
+    }
+}
+
+/// A reviewer started on a completed task. It owns `runtime` until it exits; the task's own
+/// agent, agent kind and restart policy stay as they were.
+pub(crate) struct ReviewPass {
+    pub(crate) reviewer: AgentKind,
+    pub(crate) agent: Box<dyn Agent>,
 }
 
 pub(crate) struct TaskRuntime {
@@ -54,9 +109,80 @@ impl TaskManager {
         app: &AppHandle,
     ) {
         if record.summary.status != status {
-            record.summary.status = status;
-            emit_status(app, &record.summary);
+            self.set_status(record, status, app);
+        }
+    }
+
+    /// Updates the task status, records the transition in the task history and notifies the UI.
+    ///
+    /// Leaving a billable status adds the time spent in it to the time-tracking store.
+    pub(crate) fn set_status(&self, record: &mut TaskRecord, status: TaskStatus, app: &AppHandle) {
+        let previous = record.summary.status;
+        record.summary.status = status;
+        if previous != status {
+            let now = Utc::now();
+            if let Some(category) = TimeCategory::for_status(previous) {
+                record_interval_logged(&record.summary, category, record.status_since, now);
+            }
+            record.status_since = now;
+            history::record_transition(&record.summary, previous, now);
+        }
+        if matches!(
+            status,
+            TaskStatus::Stopped
+                | TaskStatus::Discarded
+                | TaskStatus::Completed
+                | TaskStatus::Failed
+        ) {
+            let session = self.inner.activity.lock().remove(&record.summary.task_id);
+            if let Some(session) = session {
+                let (start, end) = session.close();
+                record_interval_logged(&record.summary, TimeCategory::Human, start, end);
+            }
+        }
+        emit_status(app, &record.summary);
+    }
+
+    /// Bills human time for input the user typed into the task's agent terminal.
+    pub(crate) fn record_terminal_activity(&self, task_id: Uuid) {
+        let now = Utc::now();
+        let finished = {
+            let mut activity = self.inner.activity.lock();
+            match activity.get_mut(&task_id) {
+                Some(session) => session.touch(now),
+                None => {
+                    activity.insert(task_id, ActivitySession::new(now));
+                    None
+                }
+            }
+        };
+        let Some((start, end)) = finished else {
+            return;
+        };
+        if let Some(record) = self.inner.tasks.read().get(&task_id) {
+            record_interval_logged(&record.summary, TimeCategory::Human, start, end);
+        }
+    }
+
+    /// Bills the time of every open activity session and billable status up to now and waits
+    /// for the writes, so closing the app loses nothing.
+    pub fn flush_time_tracking(&self) {
+        let now = Utc::now();
+        let mut tasks = self.inner.tasks.write();
+        let mut activity = self.inner.activity.lock();
+        for record in tasks.values_mut() {
+            if let Some(category) = TimeCategory::for_status(record.summary.status) {
+                record_interval_logged(&record.summary, category, record.status_since, now);
+                record.status_since = now;
+            }
+            if let Some(session) = activity.remove(&record.summary.task_id) {
+                let (start, end) = session.close();
+                record_interval_logged(&record.summary, TimeCategory::Human, start, end);
+            }
         }
+        drop(activity);
+        drop(tasks);
+        store_writer::flush();
     }
 
     pub fn handle_agent_status(&self, task_id: Uuid, status: TaskStatus, app: &AppHandle) {
@@ -90,6 +216,354 @@ impl TaskManager {
             );
         }
         emit_terminal_exit(app, task_id, exit_code, TerminalKind::Agent);
+        self.schedule_restart(task_id, exit_code, app);
+        self.handle_review_pass(task_id, app);
+    }
+
+    /// Starts a review pass when the task's agent completed and the task asked for an automatic
+    /// review.
+    fn handle_review_pass(&self, task_id: Uuid, app: &AppHandle) {
+        let reviewer = {
+            let tasks = self.inner.tasks.read();
+            let Some(record) = tasks.get(&task_id) else {
+                return;
+            };
+            if record.summary.status != TaskStatus::Completed || record.runtime.is_some() {
+                return;
+            }
+            let Some(reviewer) = record.auto_review else {
+                return;
+            };
+            reviewer
+        };
+        info!(
+            "starting {} to review task {}",
+            agent_label(reviewer),
+            task_id
+        );
+        if let Err(err) = self.start_review_pass(task_id, reviewer, app) {
+            warn!("failed to start review pass for task {}: {}", task_id, err);
+        }
+    }
+
+    /// Runs `reviewer` as a new, sandboxed session in the task's agent terminal.
+    fn start_review_pass(&self, task_id: Uuid, reviewer: AgentKind, app: &AppHandle) -> Result<()> {
+        let (worktree_path, base_repo_path) = {
+            let tasks = self.inner.tasks.read();
+            let record = tasks.get(&task_id).ok_or(TaskError::NotFound)?;
+            (
+                PathBuf::from(&record.summary.worktree_path),
+                PathBuf::from(&record.summary.base_repo_path),
+            )
+        };
+        if let Err(err) = install_repo_skills(&base_repo_path, &worktree_path, reviewer) {
+            warn!(
+                "failed to install repo skills for the review of task {}: {:#}",
+                task_id, err
+            );
+        }
+
+        let output_manager = self.clone();
+        let output_app = app.clone();
+        let status_manager = self.clone();
+        let status_app = app.clone();
+        let exit_manager = self.clone();
+        let exit_app = app.clone();
+        let callbacks = AgentCallbacks {
+            on_output: Arc::new(move |chunk: String| {
+                output_manager.handle_agent_output(task_id, chunk, &output_app);
+            }),
+            on_status: Arc::new(move |status: TaskStatus| {
+                status_manager.handle_review_status(task_id, status, &status_app);
+            }),
+            on_exit: Arc::new(move |exit_code: i32| {
+                exit_manager.handle_review_exit(task_id, exit_code, &exit_app);
+            }),
+        };
+
+        let mut tasks = self.inner.tasks.write();
+        let record = tasks.get_mut(&task_id).ok_or(TaskError::NotFound)?;
+        if record.runtime.is_some() {
+            return Err(TaskError::AlreadyRunning);
+        }
+        let prompt = auto_review::build_prompt(&record.summary, record.agent_kind);
+        let size = record.terminal_size;
+        let mut agent = build_agent(reviewer);
+        agent.reset(
+            size.map(|size| size.rows as usize)
+                .unwrap_or(DEFAULT_SCREEN_ROWS),
+            size.map(|size| size.cols as usize)
+                .unwrap_or(DEFAULT_SCREEN_COLS),
+        );
+        let AgentRuntime {
+            child,
+            writer,
+            master,
+        } = agent
+            .start_review(
+                &worktree_path,
+                &prompt,
+                callbacks,
+                size.map(|size| size.rows).unwrap_or(DEFAULT_PTY_ROWS),
+                size.map(|size| size.cols).unwrap_or(DEFAULT_PTY_COLS),
+            )
+            .with_context(|| format!("failed to start {}", agent_label(reviewer)))?;
+        record.runtime = Some(TaskRuntime {
+            child,
+            writer,
+            master,
+        });
+        record.review_pass = Some(ReviewPass { reviewer, agent });
+        record.summary.review_status = Some(TaskStatus::Working);
+        emit_status(app, &record.summary);
+        Ok(())
+    }
+
+    /// Tracks the reviewer's status apart from the task's, so approvals it asks for show up.
+    fn handle_review_status(&self, task_id: Uuid, status: TaskStatus, app: &AppHandle) {
+        let mut tasks = self.inner.tasks.write();
+        let Some(record) = tasks.get_mut(&task_id) else {
+            return;
+        };
+        if record.review_pass.is_none() || record.summary.review_status == Some(status) {
+            return;
+        }
+        record.summary.review_status = Some(status);
+        emit_status(app, &record.summary);
+    }
+
+    /// Frees the agent terminal once a review pass exits.
+    fn handle_review_exit(&self, task_id: Uuid, exit_code: i32, app: &AppHandle) {
+        let mut tasks = self.inner.tasks.write();
+        let Some(record) = tasks.get_mut(&task_id) else {
+            return;
+        };
+        let Some(pass) = record.review_pass.take() else {
+            return;
+        };
+        info!(
+            "{} finished reviewing task {} (exit code {})",
+            agent_label(pass.reviewer),
+            task_id,
+            exit_code
+        );
+        record.runtime = None;
+        record.summary.review_status = None;
+        emit_terminal_exit(app, task_id, exit_code, TerminalKind::Agent);
+        emit_status(app, &record.summary);
+    }
+
+    /// Starts the task's agent in its worktree and wires its callbacks back into the manager.
+    ///
+    /// `agent` switches the agent kind before starting; `rows`/`cols` fall back to the last known
+    /// terminal size and then to the defaults.
+    pub(crate) fn start_agent(
+        &self,
+        task_id: Uuid,
+        agent: Option<AgentKind>,
+        rows: Option<u16>,
+        cols: Option<u16>,
+        app: &AppHandle,
+    ) -> Result<TaskSummary> {
+        let (worktree_path, base_repo_path, title, last_size, agent_kind) = {
+            let tasks = self.inner.tasks.read();
+            let record = tasks.get(&task_id).ok_or(TaskError::NotFound)?;
+            if record.runtime.is_some() {
+                return Err(TaskError::AlreadyRunning);
+            }
+            (
+                PathBuf::from(&record.summary.worktree_path),
+                PathBuf::from(&record.summary.base_repo_path),
+                record.summary.title.clone(),
+                record.terminal_size,
+                agent.unwrap_or(record.agent_kind),
+            )
+        };
+
+        if let Err(err) = install_repo_skills(&base_repo_path, &worktree_path, agent_kind) {
+            warn!(
+                "failed to install repo skills for task {}: {:#}",
+                task_id, err
+            );
+        }
+
+        let requested_rows = rows
+            .filter(|value| *value > 0)
+            .or(last_size.map(|size| size.rows));
+        let requested_cols = cols
+            .filter(|value| *value > 0)
+            .or(last_size.map(|size| size.cols));
+        let screen_rows = requested_rows
+            .map(|value| value as usize)
+            .unwrap_or(DEFAULT_SCREEN_ROWS);
+        let screen_cols = requested_cols
+            .map(|value| value as usize)
+            .unwrap_or(DEFAULT_SCREEN_COLS);
+        let pty_rows = requested_rows.unwrap_or(DEFAULT_PTY_ROWS);
+        let pty_cols = requested_cols.unwrap_or(DEFAULT_PTY_COLS);
+
+        let status_manager = self.clone();
+        let status_app = app.clone();
+        let output_manager = self.clone();
+        let output_app = app.clone();
+        let exit_manager = self.clone();
+        let exit_app = app.clone();
+        let callbacks = AgentCallbacks {
+            on_output: Arc::new(move |chunk: String| {
+                output_manager.handle_agent_output(task_id, chunk, &output_app);
+            }),
+            on_status: Arc::new(move |status: TaskStatus| {
+                status_manager.handle_agent_status(task_id, status, &status_app);
+            }),
+            on_exit: Arc::new(move |exit_code: i32| {
+                exit_manager.handle_agent_exit(task_id, exit_code, &exit_app);
+            }),
+        };
+
+        let agent_runtime = {
+            let mut tasks = self.inner.tasks.write();
+            let record = tasks.get_mut(&task_id).ok_or(TaskError::NotFound)?;
+            if let Some(requested_agent) = agent {
+                record.agent_kind = requested_agent;
+                record.agent = build_agent(requested_agent);
+            }
+            record.summary.agent_kind = record.agent_kind;
+            let label = agent_label(record.agent_kind);
+            record.agent.reset(screen_rows, screen_cols);
+            record
+                .agent
+                .start(&worktree_path, callbacks, pty_rows, pty_cols)
+                .with_context(|| format!("failed to start {} for task {}", label, title))?
+        };
+
+        let AgentRuntime {
+            child,
+            writer,
+            master,
+        } = agent_runtime;
+
+        let mut tasks = self.inner.tasks.write();
+        let record = tasks.get_mut(&task_id).ok_or(TaskError::NotFound)?;
+        record.summary.started_at = Some(Utc::now());
+        record.summary.exit_code = None;
+        if let (Some(rows), Some(cols)) = (requested_rows, requested_cols) {
+            record.terminal_size = Some(TerminalSize { rows, cols });
+        }
+        record.runtime = Some(TaskRuntime {
+            child,
+            writer,
+            master,
+        });
+        self.set_status(record, TaskStatus::Idle, app);
+        Ok(record.summary.clone())
+    }
+
+    /// Queues a backoff restart when a task with a restart policy failed.
+    fn schedule_restart(&self, task_id: Uuid, exit_code: i32, app: &AppHandle) {
+        let (attempt, delay, base_repo_path, branch_name) = {
+            let mut tasks = self.inner.tasks.write();
+            let Some(record) = tasks.get_mut(&task_id) else {
+                return;
+            };
+            let Some(policy) = record.restart_policy else {
+                return;
+            };
+            if record.summary.status != TaskStatus::Failed {
+                return;
+            }
+            let ran_for = match (record.summary.started_at, record.summary.ended_at) {
+                (Some(started), Some(ended)) => (ended - started).num_seconds().max(0) as u64,
+                _ => 0,
+            };
+            if ran_for >= policy.stable_after_secs {
+                record.summary.restart_count = 0;
+            }
+            if record.summary.restart_count >= policy.max_retries {
+                info!(
+                    "task {} exhausted {} automatic restarts",
+                    task_id, policy.max_retries
+                );
+                return;
+            }
+            record.summary.restart_count += 1;
+            emit_status(app, &record.summary);
+            let attempt = record.summary.restart_count;
+            (
+                attempt,
+                policy.delay_for_attempt(attempt),
+                PathBuf::from(&record.summary.base_repo_path),
+                record.summary.branch_name.clone(),
+            )
+        };
+
+        let failed_at = Utc::now();
+        let task_key = task_id.to_string();
+        if let Err(err) = history::update_entry(&base_repo_path, &task_key, &branch_name, |entry| {
+            entry.restarts.push(RestartAttempt {
+                attempt,
+                exit_code,
+                failed_at,
+                delay_secs: delay.as_secs(),
+                restarted_at: None,
+                error: None,
+            });
+        }) {
+            warn!(
+                "failed to record restart attempt for task {}: {}",
+                task_id, err
+            );
+        }
+
+        info!(
+            "restarting task {} in {}s (attempt {}, exit code {})",
+            task_id,
+            delay.as_secs(),
+            attempt,
+            exit_code
+        );
+        let manager = self.clone();
+        let app = app.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            let still_failed = manager
+                .inner
+                .tasks
+                .read()
+                .get(&task_id)
+                .map(|record| {
+                    record.runtime.is_none()
+                        && record.summary.status == TaskStatus::Failed
+                        && record.summary.restart_count == attempt
+                })
+                .unwrap_or(false);
+            if !still_failed {
+                return;
+            }
+            let result = manager.start_agent(task_id, None, None, None, &app);
+            if let Err(err) = &result {
+                warn!("automatic restart of task {} failed: {}", task_id, err);
+            }
+            let restarted_at = Utc::now();
+            if let Err(err) =
+                history::update_entry(&base_repo_path, &task_key, &branch_name, |entry| {
+                    if let Some(record) =
+                        entry.restarts.iter_mut().rev().find(|record| {
+                            record.attempt == attempt && record.failed_at == failed_at
+                        })
+                    {
+                        match &result {
+                            Ok(_) => record.restarted_at = Some(restarted_at),
+                            Err(err) => record.error = Some(err.to_string()),
+                        }
+                    }
+                })
+            {
+                warn!(
+                    "failed to record restart result for task {}: {}",
+                    task_id, err
+                );
+            }
+        });
     }
 
     pub(crate) fn contains_worktree_path(&self, path: &Path) -> bool {
@@ -107,6 +581,93 @@ impl TaskManager {
         Ok(PathBuf::from(&record.summary.worktree_path))
     }
 
+    /// Writes raw input to the task's agent terminal.
+    pub(crate) fn write_agent_input(&self, task_id: Uuid, data: &[u8]) -> Result<()> {
+        let writer = {
+            let tasks = self.inner.tasks.read();
+            let record = tasks.get(&task_id).ok_or(TaskError::NotFound)?;
+            match &record.runtime {
+                Some(runtime) => runtime.writer.clone(),
+                None => return Err(TaskError::NotRunning),
+            }
+        };
+        let mut writer_guard = writer.lock();
+        writer_guard
+            .write_all(data)
+            .with_context(|| "failed to write to terminal")?;
+        if let Err(err) = writer_guard.flush() {
+            warn!("failed to flush terminal input for {}: {}", task_id, err);
+        }
+        Ok(())
+    }
+
+    /// Starts the agent like [`Self::start_agent`] and sends it `prompt` once it is ready. The
+    /// returned thread yields whether the prompt was sent.
+    pub(crate) fn start_agent_with_prompt(
+        &self,
+        task_id: Uuid,
+        agent: Option<AgentKind>,
+        rows: Option<u16>,
+        cols: Option<u16>,
+        prompt: String,
+        app: &AppHandle,
+    ) -> Result<JoinHandle<Result<()>>> {
+        self.start_agent(task_id, agent, rows, cols, app)?;
+        let manager = self.clone();
+        Ok(std::thread::spawn(move || {
+            if !manager.wait_for_agent_ready(task_id, AGENT_READY_TIMEOUT) {
+                warn!(
+                    "agent for task {} was not ready in time; sending the prompt anyway",
+                    task_id
+                );
+            }
+            let sent = manager.send_agent_prompt(task_id, &prompt);
+            if let Err(err) = &sent {
+                warn!("failed to send prompt to agent for {}: {}", task_id, err);
+            }
+            sent
+        }))
+    }
+
+    /// Types `prompt` into the agent as one bracketed paste and submits it, so multi-line
+    /// text reaches the agent as a single message. Control characters other than newlines and
+    /// tabs are dropped, so the text cannot end the paste or drive the terminal. Blocks for a
+    /// moment between the paste and the Enter; async callers should run it on a blocking thread.
+    pub(crate) fn send_agent_prompt(&self, task_id: Uuid, prompt: &str) -> Result<()> {
+        let prompt: String = prompt
+            .chars()
+            .filter(|ch| !ch.is_control() || matches!(ch, '\n' | '\t'))
+            .collect();
+        let paste = format!("\x1b[200~{prompt}\x1b[201~");
+        self.write_agent_input(task_id, paste.as_bytes())?;
+        // Agents that debounce pastes would otherwise take the Enter as part of the text.
+        std::thread::sleep(PROMPT_SUBMIT_DELAY);
+        self.write_agent_input(task_id, b"\r")
+    }
+
+    /// Waits until a freshly started agent has drawn its UI and gone quiet. Returns false when
+    /// the agent stopped or `timeout` passed first.
+    pub(crate) fn wait_for_agent_ready(&self, task_id: Uuid, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut seen_output = false;
+        while Instant::now() < deadline {
+            let status = {
+                let tasks = self.inner.tasks.read();
+                match tasks.get(&task_id) {
+                    Some(record) if record.runtime.is_some() => record.summary.status,
+                    _ => return false,
+                }
+            };
+            match status {
+                TaskStatus::Idle if seen_output => return true,
+                TaskStatus::Working | TaskStatus::AwaitingApproval => seen_output = true,
+                _ => {}
+            }
+            std::thread::sleep(Duration::from_millis(250));
+        }
+        false
+    }
+
     pub(crate) fn remove_diff_watch(&self, task_id: Uuid) {
         let mut watchers = self.inner.diff_watchers.lock();
         watchers.remove(&task_id);
@@ -127,8 +688,7 @@ impl TaskManager {
             _ if exit_code == 0 => TaskStatus::Completed,
             _ => TaskStatus::Failed,
         };
-        record.summary.status = target_status;
-        emit_status(app, &record.summary);
+        self.set_status(record, target_status, app);
         Ok(())
     }
 }
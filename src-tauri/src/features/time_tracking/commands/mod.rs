@@ -1,2 +1,4 @@
+pub mod task_time_tracking_export;
 pub mod task_time_tracking_get;
 pub mod task_time_tracking_record;
+pub mod task_time_tracking_report;
@@ -0,0 +1,57 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::git::get_repo_root;
+use crate::features::tasks::TaskManager;
+use crate::features::time_tracking::report::{export_csv, export_ical, ExportFormat, ReportRange};
+use crate::features::time_tracking::{add_open_intervals, load_store};
+use crate::utils::fs::{export_path, write_replacing};
+use crate::utils::store_writer;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub base_repo_path: String,
+    pub format: ExportFormat,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// When set the export is written there; otherwise it is only returned.
+    pub output_path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub contents: String,
+    pub output_path: Option<String>,
+}
+
+#[tauri::command]
+pub async fn task_time_tracking_export(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let repo_root = get_repo_root(PathBuf::from(req.base_repo_path).as_path())
+        .map_err(|err| err.to_string())?;
+    // Intervals are written in the background; include the ones still queued.
+    store_writer::flush();
+    let mut store = load_store(&repo_root).map_err(|err| err.to_string())?;
+    add_open_intervals(&mut store, &repo_root, &manager, Utc::now());
+    let range = ReportRange {
+        from: req.from,
+        to: req.to,
+    };
+    let contents = match req.format {
+        ExportFormat::Csv => export_csv(&store, range),
+        ExportFormat::Ical => export_ical(&store, range),
+    };
+    if let Some(output_path) = &req.output_path {
+        let output_path = export_path(output_path).map_err(|err| err.to_string())?;
+        write_replacing(&output_path, contents.as_bytes()).map_err(|err| err.to_string())?;
+    }
+    Ok(Response {
+        contents,
+        output_path: req.output_path,
+    })
+}
@@ -1,10 +1,12 @@
 use crate::commands::CommandResult;
 use crate::features::tasks::git::get_repo_root;
-use crate::features::time_tracking::{load_store, save_store, BranchTimeEntry};
+use crate::features::time_tracking::{is_valid_date_key, update_store, BranchTimeEntry};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Request {
@@ -21,28 +23,33 @@ pub async fn task_time_tracking_record(req: Request) -> CommandResult<()> {
     }
     let repo_root = get_repo_root(PathBuf::from(req.base_repo_path).as_path())
         .map_err(|err| err.to_string())?;
-    let mut store = load_store(&repo_root).map_err(|err| err.to_string())?;
-    let entry = store
-        .branches
-        .entry(req.branch_name.clone())
-        .or_insert_with(|| BranchTimeEntry {
-            branch_name: req.branch_name.clone(),
-            title: req.title.clone(),
-            by_date: HashMap::new(),
-        });
-
-    if let Some(title) = req.title {
-        entry.title = Some(title);
-    }
+    update_store(&repo_root, |store| {
+        let entry = store
+            .branches
+            .entry(req.branch_name.clone())
+            .or_insert_with(|| BranchTimeEntry {
+                branch_name: req.branch_name.clone(),
+                title: req.title.clone(),
+                by_date: HashMap::new(),
+                task_id: None,
+                by_category: HashMap::new(),
+                by_task: HashMap::new(),
+                blocks: Vec::new(),
+            });
 
-    for (day, seconds) in req.days {
-        if seconds == 0 {
-            continue;
+        if let Some(title) = req.title {
+            entry.title = Some(title);
         }
-        let slot = entry.by_date.entry(day).or_insert(0);
-        *slot = slot.saturating_add(seconds);
-    }
 
-    save_store(&repo_root, &store).map_err(|err| err.to_string())?;
-    Ok(())
+        for (day, seconds) in req.days {
+            if seconds == 0 || !is_valid_date_key(&day) {
+                continue;
+            }
+            let seconds = seconds.min(SECONDS_PER_DAY);
+            let slot = entry.by_date.entry(day).or_insert(0);
+            *slot = slot.saturating_add(seconds);
+        }
+        Ok(())
+    })
+    .map_err(|err| err.to_string())
 }
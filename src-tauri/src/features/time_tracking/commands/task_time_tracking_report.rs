@@ -0,0 +1,40 @@
+use crate::commands::CommandResult;
+use crate::features::tasks::git::get_repo_root;
+use crate::features::tasks::TaskManager;
+use crate::features::time_tracking::report::{
+    build_report, ReportGroupBy, ReportRange, TimeTrackingReport,
+};
+use crate::features::time_tracking::{add_open_intervals, load_store};
+use crate::utils::store_writer;
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub base_repo_path: String,
+    pub group_by: ReportGroupBy,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+pub type Response = TimeTrackingReport;
+
+#[tauri::command]
+pub async fn task_time_tracking_report(
+    manager: tauri::State<'_, TaskManager>,
+    req: Request,
+) -> CommandResult<Response> {
+    let repo_root = get_repo_root(PathBuf::from(req.base_repo_path).as_path())
+        .map_err(|err| err.to_string())?;
+    // Intervals are written in the background; include the ones still queued.
+    store_writer::flush();
+    let mut store = load_store(&repo_root).map_err(|err| err.to_string())?;
+    add_open_intervals(&mut store, &repo_root, &manager, Utc::now());
+    let range = ReportRange {
+        from: req.from,
+        to: req.to,
+    };
+    Ok(build_report(&store, range, req.group_by))
+}
@@ -1,13 +1,30 @@
 pub mod commands;
+pub mod report;
 
 use crate::error::Result;
+use crate::features::tasks::git::get_repo_root;
+use crate::features::tasks::{TaskManager, TaskStatus, TaskSummary};
 use crate::utils::fs::ensure_illuc_dir;
+use crate::utils::json_store::{self, Migration, StoreSchema};
+use crate::utils::store_writer;
+use chrono::{DateTime, Duration, Local, NaiveTime, Utc};
+use log::warn;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-const TIME_TRACKING_VERSION: u32 = 1;
+const TIME_TRACKING_VERSION: u32 = 2;
 const TIME_TRACKING_FILE: &str = "time-tracking.json";
+const DATE_KEY_FORMAT: &str = "%Y-%m-%d";
+/// Terminal input closer together than this belongs to the same activity session.
+const ACTIVITY_IDLE_GAP_SECS: i64 = 120;
+/// Credit after the last keystroke of a session, for reading output before moving on.
+const ACTIVITY_TAIL_SECS: i64 = 30;
+/// Long sessions are persisted in slices so closing the app loses at most one slice.
+const ACTIVITY_FLUSH_SECS: i64 = 60;
+/// Intervals of one category closer together than this are merged into one work block.
+const BLOCK_MERGE_GAP_SECS: i64 = 5 * 60;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -30,30 +47,376 @@ impl Default for TimeTrackingStore {
 pub struct BranchTimeEntry {
     pub branch_name: String,
     pub title: Option<String>,
+    /// Time the task was open in the UI, as reported by the frontend.
     pub by_date: HashMap<String, u64>,
+    /// The task that last recorded time on the branch.
+    #[serde(default)]
+    pub task_id: Option<String>,
+    /// Backend-derived time per category and local day, summed over the branch's tasks.
+    #[serde(default)]
+    pub by_category: HashMap<TimeCategory, HashMap<String, u64>>,
+    /// Backend-derived time per task, category and local day.
+    #[serde(default)]
+    pub by_task: HashMap<String, HashMap<TimeCategory, HashMap<String, u64>>>,
+    /// Backend-derived intervals, used for calendar exports.
+    #[serde(default)]
+    pub blocks: Vec<WorkBlock>,
 }
 
-pub fn load_store(repo_root: &Path) -> Result<TimeTrackingStore> {
-    let path = time_tracking_path(repo_root)?;
-    if !path.exists() {
-        return Ok(TimeTrackingStore::default());
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkBlock {
+    pub category: TimeCategory,
+    #[serde(default)]
+    pub task_id: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeCategory {
+    Agent,
+    AwaitingApproval,
+    Human,
+}
+
+impl TimeCategory {
+    /// The category billed while a task sits in `status`, if any.
+    pub fn for_status(status: TaskStatus) -> Option<Self> {
+        match status {
+            TaskStatus::Working => Some(Self::Agent),
+            TaskStatus::AwaitingApproval => Some(Self::AwaitingApproval),
+            _ => None,
+        }
     }
-    let contents = std::fs::read_to_string(&path)?;
-    let parsed: TimeTrackingStore = serde_json::from_str(&contents).unwrap_or_default();
-    if parsed.version != TIME_TRACKING_VERSION {
-        return Ok(TimeTrackingStore::default());
+}
+
+const TIME_TRACKING_SCHEMA: StoreSchema = StoreSchema {
+    version: TIME_TRACKING_VERSION,
+    migrations: &[(1, attribute_time_to_tasks as Migration)],
+};
+
+/// v1 kept one task id per branch; v2 records the task of every interval. Existing time is
+/// attributed to the branch's last task, the only owner v1 knew.
+fn attribute_time_to_tasks(mut value: Value) -> Result<Value> {
+    let branches = value
+        .get_mut("branches")
+        .and_then(Value::as_object_mut)
+        .into_iter()
+        .flat_map(|branches| branches.values_mut());
+    for entry in branches {
+        let Some(task_id) = entry
+            .get("taskId")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if let Some(by_category) = entry.get("byCategory").cloned() {
+            let mut by_task = Map::new();
+            by_task.insert(task_id.clone(), by_category);
+            entry["byTask"] = Value::Object(by_task);
+        }
+        if let Some(blocks) = entry.get_mut("blocks").and_then(Value::as_array_mut) {
+            for block in blocks {
+                block["taskId"] = Value::from(task_id.clone());
+            }
+        }
     }
-    Ok(parsed)
+    Ok(value)
 }
 
-pub fn save_store(repo_root: &Path, store: &TimeTrackingStore) -> Result<()> {
-    let path = time_tracking_path(repo_root)?;
-    let payload = serde_json::to_string_pretty(store).map_err(|err| anyhow::Error::from(err))?;
-    std::fs::write(path, payload)?;
-    Ok(())
+pub fn load_store(repo_root: &Path) -> Result<TimeTrackingStore> {
+    json_store::load(&time_tracking_path(repo_root)?, &TIME_TRACKING_SCHEMA)
+}
+
+/// Applies `update` to the store while holding its lock, then writes the result back.
+pub fn update_store<R, F>(repo_root: &Path, update: F) -> Result<R>
+where
+    F: FnOnce(&mut TimeTrackingStore) -> Result<R>,
+{
+    json_store::update(
+        &time_tracking_path(repo_root)?,
+        &TIME_TRACKING_SCHEMA,
+        update,
+    )
 }
 
 fn time_tracking_path(repo_root: &Path) -> Result<PathBuf> {
     let illuc_dir = ensure_illuc_dir(repo_root)?;
     Ok(illuc_dir.join(TIME_TRACKING_FILE))
 }
+
+/// Splits `[start, end)` into seconds per local calendar day (`YYYY-MM-DD`).
+pub fn split_by_local_date(start: DateTime<Utc>, end: DateTime<Utc>) -> Vec<(String, u64)> {
+    let mut result = Vec::new();
+    let end = end.with_timezone(&Local);
+    let mut cursor = start.with_timezone(&Local);
+    while cursor < end {
+        let next_midnight = cursor
+            .date_naive()
+            .succ_opt()
+            .and_then(|day| {
+                day.and_time(NaiveTime::MIN)
+                    .and_local_timezone(Local)
+                    .earliest()
+            })
+            .unwrap_or(end);
+        let slice_end = next_midnight.min(end).max(cursor);
+        let seconds = (slice_end - cursor).num_seconds().max(0) as u64;
+        if seconds > 0 {
+            result.push((cursor.format(DATE_KEY_FORMAT).to_string(), seconds));
+        }
+        if slice_end == cursor {
+            break;
+        }
+        cursor = slice_end;
+    }
+    result
+}
+
+/// Adds the interval `[start, end)` to the task's branch under `category`.
+pub fn record_interval(
+    summary: &TaskSummary,
+    category: TimeCategory,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<()> {
+    if summary.branch_name.trim().is_empty() || split_by_local_date(start, end).is_empty() {
+        return Ok(());
+    }
+    let repo_root = PathBuf::from(&summary.base_repo_path);
+    update_store(&repo_root, |store| {
+        add_interval(store, summary, category, start, end);
+        Ok(())
+    })
+}
+
+/// Adds the time the tasks of `repo_root` have spent in their current billable status up to
+/// `now`. It is only written once they leave the status, so reports add it themselves.
+pub fn add_open_intervals(
+    store: &mut TimeTrackingStore,
+    repo_root: &Path,
+    manager: &TaskManager,
+    now: DateTime<Utc>,
+) {
+    let open: Vec<(TaskSummary, TimeCategory, DateTime<Utc>)> = manager
+        .inner
+        .tasks
+        .read()
+        .values()
+        .filter_map(|record| {
+            let category = TimeCategory::for_status(record.summary.status)?;
+            Some((record.summary.clone(), category, record.status_since))
+        })
+        .collect();
+    for (summary, category, since) in open {
+        let in_repo =
+            get_repo_root(Path::new(&summary.base_repo_path)).is_ok_and(|root| root == repo_root);
+        if in_repo {
+            add_interval(store, &summary, category, since, now);
+        }
+    }
+}
+
+fn add_interval(
+    store: &mut TimeTrackingStore,
+    summary: &TaskSummary,
+    category: TimeCategory,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    let days = split_by_local_date(start, end);
+    if days.is_empty() || summary.branch_name.trim().is_empty() {
+        return;
+    }
+    let entry = store
+        .branches
+        .entry(summary.branch_name.clone())
+        .or_insert_with(|| BranchTimeEntry {
+            branch_name: summary.branch_name.clone(),
+            title: Some(summary.title.clone()),
+            by_date: HashMap::new(),
+            task_id: None,
+            by_category: HashMap::new(),
+            by_task: HashMap::new(),
+            blocks: Vec::new(),
+        });
+    let task_id = summary.task_id.to_string();
+    entry.task_id = Some(task_id.clone());
+    let by_task = entry
+        .by_task
+        .entry(task_id.clone())
+        .or_default()
+        .entry(category)
+        .or_default();
+    let by_date = entry.by_category.entry(category).or_default();
+    for (day, seconds) in days {
+        let slot = by_task.entry(day.clone()).or_insert(0);
+        *slot = slot.saturating_add(seconds);
+        let slot = by_date.entry(day).or_insert(0);
+        *slot = slot.saturating_add(seconds);
+    }
+    push_block(&mut entry.blocks, &task_id, category, start, end);
+}
+
+fn push_block(
+    blocks: &mut Vec<WorkBlock>,
+    task_id: &str,
+    category: TimeCategory,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    let mergeable = blocks
+        .iter_mut()
+        .rev()
+        .find(|block| block.category == category && block.task_id.as_deref() == Some(task_id));
+    if let Some(block) = mergeable {
+        if start >= block.start && start - block.end <= Duration::seconds(BLOCK_MERGE_GAP_SECS) {
+            block.end = block.end.max(end);
+            return;
+        }
+    }
+    blocks.push(WorkBlock {
+        category,
+        task_id: Some(task_id.to_string()),
+        start,
+        end,
+    });
+}
+
+/// Like [`record_interval`], but runs on the store writer thread and logs failures instead of
+/// returning them, so callers may hold the task lock.
+pub fn record_interval_logged(
+    summary: &TaskSummary,
+    category: TimeCategory,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    let summary = summary.clone();
+    store_writer::queue(move || {
+        if let Err(err) = record_interval(&summary, category, start, end) {
+            warn!(
+                "failed to record {:?} time for task {}: {}",
+                category, summary.task_id, err
+            );
+        }
+    });
+}
+
+/// A run of human terminal input, used to bill `TimeCategory::Human`.
+#[derive(Debug, Clone, Copy)]
+pub struct ActivitySession {
+    start: DateTime<Utc>,
+    last: DateTime<Utc>,
+}
+
+impl ActivitySession {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            start: now,
+            last: now,
+        }
+    }
+
+    /// Registers input at `now` and returns an interval that is ready to be persisted.
+    pub fn touch(&mut self, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        if now - self.last > Duration::seconds(ACTIVITY_IDLE_GAP_SECS) {
+            let finished = self.close();
+            *self = Self::new(now);
+            return Some(finished);
+        }
+        self.last = now;
+        if now - self.start >= Duration::seconds(ACTIVITY_FLUSH_SECS) {
+            let slice = (self.start, now);
+            self.start = now;
+            return Some(slice);
+        }
+        None
+    }
+
+    /// Ends the session, crediting a short tail after the last input.
+    pub fn close(&self) -> (DateTime<Utc>, DateTime<Utc>) {
+        (
+            self.start,
+            self.last + Duration::seconds(ACTIVITY_TAIL_SECS),
+        )
+    }
+}
+
+/// Whether `day` is a `YYYY-MM-DD` key as used by the store.
+pub fn is_valid_date_key(day: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(day, DATE_KEY_FORMAT).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{attribute_time_to_tasks, push_block, TimeCategory, WorkBlock};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn blocks_merge_only_within_one_task_and_category() {
+        let mut blocks: Vec<WorkBlock> = Vec::new();
+        push_block(&mut blocks, "a", TimeCategory::Agent, at(0), at(10));
+        push_block(&mut blocks, "a", TimeCategory::Agent, at(13), at(20));
+        push_block(&mut blocks, "b", TimeCategory::Agent, at(21), at(25));
+        push_block(&mut blocks, "a", TimeCategory::Human, at(22), at(24));
+        push_block(&mut blocks, "a", TimeCategory::Agent, at(40), at(45));
+
+        let spans: Vec<(Option<&str>, i64, i64)> = blocks
+            .iter()
+            .map(|block| {
+                let minutes = |time: DateTime<Utc>| (time - at(0)).num_minutes();
+                (
+                    block.task_id.as_deref(),
+                    minutes(block.start),
+                    minutes(block.end),
+                )
+            })
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (Some("a"), 0, 20),
+                (Some("b"), 21, 25),
+                (Some("a"), 22, 24),
+                (Some("a"), 40, 45),
+            ]
+        );
+    }
+
+    #[test]
+    fn migration_attributes_existing_time_to_the_last_task() {
+        let v1 = json!({
+            "version": 1,
+            "branches": {
+                "feature": {
+                    "branchName": "feature",
+                    "byDate": {},
+                    "taskId": "task",
+                    "byCategory": { "agent": { "2024-05-01": 60 } },
+                    "blocks": [{
+                        "category": "agent",
+                        "start": "2024-05-01T09:00:00Z",
+                        "end": "2024-05-01T09:01:00Z"
+                    }]
+                }
+            }
+        });
+
+        let v2 = attribute_time_to_tasks(v1).unwrap();
+
+        let entry = &v2["branches"]["feature"];
+        assert_eq!(
+            entry["byTask"],
+            json!({ "task": { "agent": { "2024-05-01": 60 } } })
+        );
+        assert_eq!(entry["blocks"][0]["taskId"], "task");
+    }
+}
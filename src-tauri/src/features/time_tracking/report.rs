@@ -0,0 +1,429 @@
+use super::{BranchTimeEntry, TimeCategory, TimeTrackingStore, DATE_KEY_FORMAT};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+const ICAL_DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+const ICAL_LINE_LIMIT: usize = 75;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportGroupBy {
+    Branch,
+    Task,
+    Day,
+    Week,
+    Month,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Ical,
+}
+
+/// Inclusive range of local days; open ends are unbounded.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRange {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl ReportRange {
+    pub fn contains(&self, day: NaiveDate) -> bool {
+        self.from.is_none_or(|from| day >= from) && self.to.is_none_or(|to| day <= to)
+    }
+
+    fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        let start_day = start.with_timezone(&Local).date_naive();
+        let end_day = end.with_timezone(&Local).date_naive();
+        self.from.is_none_or(|from| end_day >= from) && self.to.is_none_or(|to| start_day <= to)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportRow {
+    pub key: String,
+    pub label: String,
+    /// Time the task was open in the UI.
+    pub open_seconds: u64,
+    /// Backend-derived time; categories can overlap (a human typing while the agent works).
+    pub seconds_by_category: HashMap<TimeCategory, u64>,
+}
+
+impl ReportRow {
+    fn add(&mut self, category: Option<TimeCategory>, seconds: u64) {
+        let slot = match category {
+            Some(category) => self.seconds_by_category.entry(category).or_insert(0),
+            None => &mut self.open_seconds,
+        };
+        *slot = slot.saturating_add(seconds);
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeTrackingReport {
+    pub range: ReportRange,
+    pub group_by: ReportGroupBy,
+    pub rows: Vec<ReportRow>,
+    pub totals: ReportRow,
+}
+
+/// One day of one category for one branch and task; `category: None` is UI-open time.
+struct Fact<'a> {
+    entry: &'a BranchTimeEntry,
+    task_id: Option<&'a str>,
+    day: NaiveDate,
+    category: Option<TimeCategory>,
+    seconds: u64,
+}
+
+fn collect_facts(store: &TimeTrackingStore, range: ReportRange) -> Vec<Fact<'_>> {
+    let mut facts = Vec::new();
+    for entry in store.branches.values() {
+        // The UI reports open time per branch; it belongs to a task only when the branch had
+        // just one.
+        let open_task = match entry.by_task.len() {
+            1 => entry.by_task.keys().next().map(String::as_str),
+            _ => None,
+        };
+        let open = entry
+            .by_date
+            .iter()
+            .map(|(day, seconds)| (open_task, None, day, *seconds));
+        let derived = entry.by_task.iter().flat_map(|(task_id, categories)| {
+            categories.iter().flat_map(move |(category, days)| {
+                days.iter().map(move |(day, seconds)| {
+                    (Some(task_id.as_str()), Some(*category), day, *seconds)
+                })
+            })
+        });
+        for (task_id, category, day, seconds) in open.chain(derived) {
+            let Ok(day) = NaiveDate::parse_from_str(day, DATE_KEY_FORMAT) else {
+                continue;
+            };
+            if seconds == 0 || !range.contains(day) {
+                continue;
+            }
+            facts.push(Fact {
+                entry,
+                task_id,
+                day,
+                category,
+                seconds,
+            });
+        }
+    }
+    facts.sort_by(|left, right| {
+        (
+            left.day,
+            &left.entry.branch_name,
+            left.task_id,
+            category_key(left.category),
+        )
+            .cmp(&(
+                right.day,
+                &right.entry.branch_name,
+                right.task_id,
+                category_key(right.category),
+            ))
+    });
+    facts
+}
+
+fn group_key(fact: &Fact<'_>, group_by: ReportGroupBy) -> (String, String) {
+    let title = fact
+        .entry
+        .title
+        .clone()
+        .unwrap_or_else(|| fact.entry.branch_name.clone());
+    match group_by {
+        ReportGroupBy::Branch => (fact.entry.branch_name.clone(), title),
+        ReportGroupBy::Task => (
+            fact.task_id.unwrap_or(&fact.entry.branch_name).to_string(),
+            title,
+        ),
+        ReportGroupBy::Day => {
+            let key = fact.day.format(DATE_KEY_FORMAT).to_string();
+            (key.clone(), key)
+        }
+        ReportGroupBy::Week => {
+            let week = fact.day.iso_week();
+            let key = format!("{}-W{:02}", week.year(), week.week());
+            (key.clone(), key)
+        }
+        ReportGroupBy::Month => {
+            let key = fact.day.format("%Y-%m").to_string();
+            (key.clone(), key)
+        }
+    }
+}
+
+pub fn build_report(
+    store: &TimeTrackingStore,
+    range: ReportRange,
+    group_by: ReportGroupBy,
+) -> TimeTrackingReport {
+    let mut rows: BTreeMap<String, ReportRow> = BTreeMap::new();
+    let mut totals = ReportRow {
+        key: "total".to_string(),
+        label: "Total".to_string(),
+        ..ReportRow::default()
+    };
+    for fact in collect_facts(store, range) {
+        let (key, label) = group_key(&fact, group_by);
+        rows.entry(key.clone())
+            .or_insert_with(|| ReportRow {
+                key,
+                label,
+                ..ReportRow::default()
+            })
+            .add(fact.category, fact.seconds);
+        totals.add(fact.category, fact.seconds);
+    }
+    TimeTrackingReport {
+        range,
+        group_by,
+        rows: rows.into_values().collect(),
+        totals,
+    }
+}
+
+fn category_key(category: Option<TimeCategory>) -> &'static str {
+    match category {
+        None => "open",
+        Some(TimeCategory::Agent) => "agent",
+        Some(TimeCategory::AwaitingApproval) => "awaiting-approval",
+        Some(TimeCategory::Human) => "human",
+    }
+}
+
+fn category_label(category: TimeCategory) -> &'static str {
+    match category {
+        TimeCategory::Agent => "Agent working",
+        TimeCategory::AwaitingApproval => "Awaiting approval",
+        TimeCategory::Human => "Hands-on",
+    }
+}
+
+/// One row per day, branch, task and category.
+pub fn export_csv(store: &TimeTrackingStore, range: ReportRange) -> String {
+    let mut output = String::from("date,branch,task_id,title,category,seconds,hours\n");
+    for fact in collect_facts(store, range) {
+        let fields = [
+            fact.day.format(DATE_KEY_FORMAT).to_string(),
+            fact.entry.branch_name.clone(),
+            fact.task_id.unwrap_or_default().to_string(),
+            fact.entry.title.clone().unwrap_or_default(),
+            category_key(fact.category).to_string(),
+            fact.seconds.to_string(),
+            format!("{:.2}", fact.seconds as f64 / 3600.0),
+        ];
+        let line = fields
+            .iter()
+            .map(|field| csv_escape(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        output.push_str(&line);
+        output.push('\n');
+    }
+    output
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One event per recorded work block overlapping the range.
+pub fn export_ical(store: &TimeTrackingStore, range: ReportRange) -> String {
+    let stamp = Utc::now().format(ICAL_DATETIME_FORMAT).to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//illuc//Time Tracking//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    let mut entries: Vec<&BranchTimeEntry> = store.branches.values().collect();
+    entries.sort_by(|left, right| left.branch_name.cmp(&right.branch_name));
+    for entry in entries {
+        let title = entry.title.as_deref().unwrap_or(&entry.branch_name);
+        for block in &entry.blocks {
+            let owner = block.task_id.as_deref().unwrap_or(&entry.branch_name);
+            if block.end <= block.start || !range.overlaps(block.start, block.end) {
+                continue;
+            }
+            let label = category_label(block.category);
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!(
+                "UID:{}-{}-{}@illuc",
+                owner,
+                category_key(Some(block.category)),
+                block.start.timestamp()
+            ));
+            lines.push(format!("DTSTAMP:{stamp}"));
+            lines.push(format!(
+                "DTSTART:{}",
+                block.start.format(ICAL_DATETIME_FORMAT)
+            ));
+            lines.push(format!("DTEND:{}", block.end.format(ICAL_DATETIME_FORMAT)));
+            lines.push(format!(
+                "SUMMARY:{}",
+                ical_escape(&format!("{title} ({label})"))
+            ));
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                ical_escape(&format!("Branch: {}", entry.branch_name))
+            ));
+            lines.push(format!("CATEGORIES:{}", ical_escape(label)));
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold_ical_line(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+fn ical_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line at 75 octets as required by RFC 5545.
+fn fold_ical_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for ch in line.chars() {
+        let len = ch.len_utf8();
+        if width + len > ICAL_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(ch);
+        width += len;
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_report, export_csv, export_ical, fold_ical_line, ReportGroupBy, ReportRange,
+    };
+    use crate::features::time_tracking::{
+        BranchTimeEntry, TimeCategory, TimeTrackingStore, WorkBlock,
+    };
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    fn days(entries: &[(&str, u64)]) -> HashMap<String, u64> {
+        entries
+            .iter()
+            .map(|(day, seconds)| (day.to_string(), *seconds))
+            .collect()
+    }
+
+    fn store_with(entry: BranchTimeEntry) -> TimeTrackingStore {
+        let mut store = TimeTrackingStore::default();
+        store.branches.insert(entry.branch_name.clone(), entry);
+        store
+    }
+
+    fn entry(branch_name: &str, title: &str) -> BranchTimeEntry {
+        BranchTimeEntry {
+            branch_name: branch_name.to_string(),
+            title: Some(title.to_string()),
+            by_date: HashMap::new(),
+            task_id: None,
+            by_category: HashMap::new(),
+            by_task: HashMap::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn task_report_splits_a_branch_between_its_tasks() {
+        let mut branch = entry("feature", "Feature");
+        branch.task_id = Some("second".to_string());
+        branch.by_date = days(&[("2024-05-01", 600)]);
+        for (task_id, seconds) in [("first", 300), ("second", 120)] {
+            branch.by_task.insert(
+                task_id.to_string(),
+                HashMap::from([(TimeCategory::Agent, days(&[("2024-05-01", seconds)]))]),
+            );
+        }
+        let store = store_with(branch);
+
+        let report = build_report(&store, ReportRange::default(), ReportGroupBy::Task);
+
+        let agent_seconds: Vec<(&str, u64)> = report
+            .rows
+            .iter()
+            .map(|row| {
+                let seconds = row.seconds_by_category.get(&TimeCategory::Agent);
+                (row.key.as_str(), seconds.copied().unwrap_or(0))
+            })
+            .collect();
+        assert_eq!(
+            agent_seconds,
+            vec![("feature", 0), ("first", 300), ("second", 120)]
+        );
+        // Open time of a branch shared by two tasks stays with the branch.
+        assert_eq!(report.rows[0].open_seconds, 600);
+        assert_eq!(report.totals.open_seconds, 600);
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_separators() {
+        let mut branch = entry("fix,comma", "Say \"hi\"\nthen leave");
+        branch.by_date = days(&[("2024-05-01", 5400)]);
+        let store = store_with(branch);
+
+        let csv = export_csv(&store, ReportRange::default());
+
+        assert_eq!(
+            csv,
+            "date,branch,task_id,title,category,seconds,hours\n\
+             2024-05-01,\"fix,comma\",,\"Say \"\"hi\"\"\nthen leave\",open,5400,1.50\n"
+        );
+    }
+
+    #[test]
+    fn ical_escapes_text_and_folds_long_lines() {
+        let mut branch = entry("feature", "Fix; a, b\\c");
+        branch.blocks.push(WorkBlock {
+            category: TimeCategory::Human,
+            task_id: Some("task".to_string()),
+            start: Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap(),
+            end: Utc.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+        });
+        let store = store_with(branch);
+
+        let ical = export_ical(&store, ReportRange::default());
+
+        assert!(ical.contains("UID:task-human-1714554000@illuc\r\n"));
+        assert!(ical.contains("SUMMARY:Fix\\; a\\, b\\\\c (Hands-on)\r\n"));
+        assert!(ical.contains("DTSTART:20240501T090000Z\r\n"));
+
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_ical_line(&line);
+        assert!(folded.split("\r\n").all(|part| part.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
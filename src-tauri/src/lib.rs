@@ -15,15 +15,23 @@ use crate::features::tasks::review::skills::install_predefined_skills_on_startup
 #[cfg(target_os = "windows")]
 use crate::features::shell::native_titlebar::apply_windows_caption_color;
 use crate::features::tasks::git::commands::task_git_commit::task_git_commit;
+use crate::features::tasks::git::commands::task_git_diff_file_get::task_git_diff_file_get;
 use crate::features::tasks::git::commands::task_git_diff_get::task_git_diff_get;
+use crate::features::tasks::git::commands::task_git_diff_summary::task_git_diff_summary;
 use crate::features::tasks::git::commands::task_git_diff_watch_start::task_git_diff_watch_start;
 use crate::features::tasks::git::commands::task_git_diff_watch_stop::task_git_diff_watch_stop;
+use crate::features::tasks::git::commands::task_git_discard::task_git_discard;
+use crate::features::tasks::git::commands::task_git_file_lines_get::task_git_file_lines_get;
 use crate::features::tasks::git::commands::task_git_has_changes::task_git_has_changes;
 use crate::features::tasks::git::commands::task_git_list_branches::task_git_list_branches;
+use crate::features::tasks::git::commands::task_git_list_commits::task_git_list_commits;
 use crate::features::tasks::git::commands::task_git_push::task_git_push;
+use crate::features::tasks::git::commands::task_git_stage::task_git_stage;
+use crate::features::tasks::git::commands::task_git_unstage::task_git_unstage;
 use crate::features::tasks::management::commands::select_base_repo::select_base_repo;
 use crate::features::tasks::management::commands::task_create::task_create;
 use crate::features::tasks::management::commands::task_discard::task_discard;
+use crate::features::tasks::management::commands::task_history_get::task_history_get;
 use crate::features::tasks::management::commands::task_load_existing::task_load_existing;
 use crate::features::tasks::management::commands::task_open_worktree_in_vscode::task_open_worktree_in_vscode;
 use crate::features::tasks::management::commands::task_open_worktree_terminal::task_open_worktree_terminal;
@@ -33,21 +41,36 @@ use crate::features::tasks::management::commands::task_terminal_resize::task_ter
 use crate::features::tasks::management::commands::task_terminal_start::task_terminal_start;
 use crate::features::tasks::management::commands::task_terminal_write::task_terminal_write;
 use crate::features::tasks::review::commands::task_review_add_comment::task_review_add_comment;
+use crate::features::tasks::review::commands::task_review_apply_suggestion::task_review_apply_suggestion;
 use crate::features::tasks::review::commands::task_review_delete_comment::task_review_delete_comment;
 use crate::features::tasks::review::commands::task_review_edit_comment::task_review_edit_comment;
+use crate::features::tasks::review::commands::task_review_export::task_review_export;
 use crate::features::tasks::review::commands::task_review_get::task_review_get;
 use crate::features::tasks::review::commands::task_review_get_user_display_name::task_review_get_user_display_name;
+use crate::features::tasks::review::commands::task_review_set_file_viewed::task_review_set_file_viewed;
+use crate::features::tasks::review::commands::task_review_set_verdict::task_review_set_verdict;
+use crate::features::tasks::review::commands::task_review_submit::task_review_submit;
 use crate::features::tasks::review::commands::task_review_update_thread_status::task_review_update_thread_status;
+use crate::features::tasks::review::skills::commands::skills_list::skills_list;
+use crate::features::tasks::review::skills::commands::skills_reinstall::skills_reinstall;
+use crate::features::tasks::review::skills::commands::skills_uninstall::skills_uninstall;
 use crate::features::tasks::TaskManager;
 use crate::features::theming::apply_startup_webview_window_css;
 use crate::features::theming::apply_startup_window_background;
 use crate::features::theming::on_page_load as theming_on_page_load;
+use crate::features::time_tracking::commands::task_time_tracking_export::task_time_tracking_export;
 use crate::features::time_tracking::commands::task_time_tracking_get::task_time_tracking_get;
 use crate::features::time_tracking::commands::task_time_tracking_record::task_time_tracking_record;
+use crate::features::time_tracking::commands::task_time_tracking_report::task_time_tracking_report;
 use log::info;
-#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
 use tauri::Manager;
 
+/// Entry point of the `illuc-cli` binary; returns the process exit code.
+pub fn run_cli() -> i32 {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    features::tasks::review::cli::run(&args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     if let Err(error) = dotenvy::dotenv() {
@@ -87,7 +110,7 @@ pub fn run() {
                 log::warn!("failed to start settings/theme watcher: {error}");
             }
 
-            if let Err(error) = install_predefined_skills_on_startup() {
+            if let Err(error) = install_predefined_skills_on_startup(app.handle()) {
                 log::warn!("failed to install predefined skills: {error}");
             }
 
@@ -117,13 +140,21 @@ pub fn run() {
             task_start,
             task_stop,
             task_discard,
+            task_history_get,
             task_terminal_write,
             task_terminal_resize,
             task_terminal_start,
             task_git_diff_get,
+            task_git_diff_summary,
+            task_git_diff_file_get,
+            task_git_file_lines_get,
+            task_git_list_commits,
             task_git_has_changes,
             task_git_diff_watch_start,
             task_git_diff_watch_stop,
+            task_git_stage,
+            task_git_unstage,
+            task_git_discard,
             task_git_commit,
             task_git_push,
             task_load_existing,
@@ -134,17 +165,32 @@ pub fn run() {
             open_path_terminal,
             open_path_in_explorer,
             task_git_list_branches,
+            task_time_tracking_export,
             task_time_tracking_get,
             task_time_tracking_record,
+            task_time_tracking_report,
             task_review_get,
             task_review_add_comment,
+            task_review_apply_suggestion,
             task_review_edit_comment,
+            task_review_export,
             task_review_delete_comment,
             task_review_get_user_display_name,
+            task_review_set_file_viewed,
+            task_review_set_verdict,
+            task_review_submit,
             task_review_update_thread_status,
             settings_open_in_vscode,
-            settings_theme_get
+            settings_theme_get,
+            skills_list,
+            skills_reinstall,
+            skills_uninstall
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                app.state::<TaskManager>().flush_time_tracking();
+            }
+        });
 }
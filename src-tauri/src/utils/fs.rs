@@ -1,4 +1,6 @@
 use crate::error::{Result, TaskError};
+use std::fs::File;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 const ILLUC_GITIGNORE_CONTENTS: &str = "*\n.gitignore\n";
@@ -51,6 +53,47 @@ pub fn ensure_illuc_dir(base_path: &Path) -> Result<PathBuf> {
     Ok(illuc_dir)
 }
 
+/// Writes `contents` to a temp file next to `path` and renames it over `path`, so readers never
+/// see a partly written file.
+pub fn write_replacing(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".tmp-{}", std::process::id()));
+    let temp_path = path.with_file_name(temp_name);
+    let written = (|| -> Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// Checks a caller-chosen export destination: an absolute path to a file, not a directory, in
+/// a directory that exists.
+pub fn export_path(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() || path.file_name().is_none() {
+        return Err(TaskError::Message(format!(
+            "{} is not an absolute file path",
+            path.display()
+        )));
+    }
+    if path.is_dir() {
+        return Err(TaskError::Message(format!(
+            "{} is a directory",
+            path.display()
+        )));
+    }
+    if let Some(parent) = path.parent() {
+        ensure_directory(parent)?;
+    }
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::ensure_illuc_dir;
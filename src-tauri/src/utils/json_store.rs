@@ -0,0 +1,264 @@
+//! Versioned JSON stores under `.illuc/`.
+//!
+//! Every store is guarded by an advisory lock on a sibling `<file>.lock` (shared with the
+//! skill helper scripts), written via a temp file and rename, and upgraded through a chain
+//! of migrations when an older schema version is found. Files that cannot be read are moved
+//! aside as `<file>.corrupt-<timestamp>` instead of being silently overwritten.
+
+use crate::error::{Result, TaskError};
+use crate::utils::fs::write_replacing;
+use chrono::Utc;
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::{File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Upgrades a store document by exactly one schema version.
+pub type Migration = fn(Value) -> Result<Value>;
+
+pub struct StoreSchema {
+    pub version: u32,
+    /// `(from_version, migration)` pairs; each migration produces `from_version + 1`.
+    pub migrations: &'static [(u32, Migration)],
+}
+
+/// Held while a store is read or written; the lock is released on drop.
+pub struct StoreLock {
+    _file: File,
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn open_lock_file(path: &Path) -> Result<File> {
+    Ok(OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(sibling_path(path, ".lock"))?)
+}
+
+pub fn lock_exclusive(path: &Path) -> Result<StoreLock> {
+    let file = open_lock_file(path)?;
+    file.lock()?;
+    Ok(StoreLock { _file: file })
+}
+
+pub fn lock_shared(path: &Path) -> Result<StoreLock> {
+    let file = open_lock_file(path)?;
+    file.lock_shared()?;
+    Ok(StoreLock { _file: file })
+}
+
+fn document_version(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .unwrap_or(0)
+}
+
+/// Reads the store when it is missing or already at the current version and readable; None
+/// when it has to be migrated or moved aside first. Callers must hold a lock on `path`.
+fn read_current<T>(path: &Path, schema: &StoreSchema) -> Result<Option<T>>
+where
+    T: DeserializeOwned + Default,
+{
+    if !path.exists() {
+        return Ok(Some(T::default()));
+    }
+    let contents = std::fs::read_to_string(path)?;
+    let Ok(value) = serde_json::from_str::<Value>(&contents) else {
+        return Ok(None);
+    };
+    if document_version(&value) != schema.version {
+        return Ok(None);
+    }
+    Ok(serde_json::from_value(value).ok())
+}
+
+/// Reads and migrates the store. Callers must hold an exclusive lock on `path`, since an
+/// unreadable file is moved aside.
+pub fn read_locked<T>(path: &Path, schema: &StoreSchema) -> Result<T>
+where
+    T: DeserializeOwned + Default,
+{
+    if !path.exists() {
+        return Ok(T::default());
+    }
+    let contents = std::fs::read_to_string(path)?;
+    let mut value: Value = match serde_json::from_str(&contents) {
+        Ok(value) => value,
+        Err(err) => return backup_and_reset(path, &format!("invalid JSON: {err}")),
+    };
+    let mut version = document_version(&value);
+    if version > schema.version {
+        return Err(TaskError::Message(format!(
+            "{} uses schema version {version}, but this build only supports up to {}.",
+            path.display(),
+            schema.version
+        )));
+    }
+    while version < schema.version {
+        let migration = schema
+            .migrations
+            .iter()
+            .find(|(from, _)| *from == version)
+            .map(|(_, migration)| migration);
+        let Some(migration) = migration else {
+            return backup_and_reset(path, &format!("no migration from version {version}"));
+        };
+        value = match migration(value) {
+            Ok(value) => value,
+            Err(err) => {
+                return backup_and_reset(
+                    path,
+                    &format!("migration from version {version} failed: {err}"),
+                )
+            }
+        };
+        version += 1;
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_string(), Value::from(version));
+        }
+    }
+    match serde_json::from_value(value) {
+        Ok(parsed) => Ok(parsed),
+        Err(err) => backup_and_reset(path, &format!("unexpected shape: {err}")),
+    }
+}
+
+/// Writes the store through a temp file and rename. Callers must hold an exclusive lock.
+pub fn write_locked<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let payload = serde_json::to_string_pretty(value).map_err(anyhow::Error::from)?;
+    write_replacing(path, payload.as_bytes())
+}
+
+/// Reads the store under a shared lock. A store that needs migrating or moving aside is
+/// handled under the exclusive lock instead, and a migrated store is written back so later
+/// reads see the same document.
+pub fn load<T>(path: &Path, schema: &StoreSchema) -> Result<T>
+where
+    T: DeserializeOwned + Serialize + Default,
+{
+    {
+        let _lock = lock_shared(path)?;
+        if let Some(store) = read_current(path, schema)? {
+            return Ok(store);
+        }
+    }
+    let _lock = lock_exclusive(path)?;
+    let store = read_locked(path, schema)?;
+    if path.exists() {
+        write_locked(path, &store)?;
+    }
+    Ok(store)
+}
+
+/// Loads, modifies and saves the store under one exclusive lock. Nothing is written when
+/// `update` fails.
+pub fn update<T, R, F>(path: &Path, schema: &StoreSchema, update: F) -> Result<R>
+where
+    T: DeserializeOwned + Serialize + Default,
+    F: FnOnce(&mut T) -> Result<R>,
+{
+    let _lock = lock_exclusive(path)?;
+    let mut store: T = read_locked(path, schema)?;
+    let result = update(&mut store)?;
+    write_locked(path, &store)?;
+    Ok(result)
+}
+
+fn backup_and_reset<T: Default>(path: &Path, reason: &str) -> Result<T> {
+    let backup_path = sibling_path(
+        path,
+        &format!(".corrupt-{}", Utc::now().format("%Y%m%dT%H%M%S%3fZ")),
+    );
+    std::fs::rename(path, &backup_path)?;
+    warn!(
+        "{} could not be read ({}); moved it to {} and started a new store",
+        path.display(),
+        reason,
+        backup_path.display()
+    );
+    Ok(T::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load, update, Migration, StoreSchema};
+    use crate::error::Result;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct Store {
+        version: u32,
+        items: Vec<String>,
+    }
+
+    fn rename_entries(mut value: Value) -> Result<Value> {
+        if let Some(entries) = value
+            .as_object_mut()
+            .and_then(|object| object.remove("entries"))
+        {
+            value["items"] = entries;
+        }
+        Ok(value)
+    }
+
+    const SCHEMA: StoreSchema = StoreSchema {
+        version: 2,
+        migrations: &[(1, rename_entries as Migration)],
+    };
+
+    fn temp_dir() -> std::path::PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        std::env::temp_dir().join(format!("illuc-json-store-test-{nanos}"))
+    }
+
+    #[test]
+    fn migrates_old_versions_and_backs_up_unreadable_files() -> Result<()> {
+        let dir = temp_dir();
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("store.json");
+
+        std::fs::write(&path, r#"{"version":1,"entries":["a"]}"#)?;
+        let migrated: Store = load(&path, &SCHEMA)?;
+        assert_eq!(migrated.version, 2);
+        assert_eq!(migrated.items, vec!["a".to_string()]);
+        let persisted: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path)?).map_err(anyhow::Error::from)?;
+        assert_eq!(persisted["version"], 2);
+
+        std::fs::write(&path, "{ not json")?;
+        update(&path, &SCHEMA, |store: &mut Store| {
+            store.version = 2;
+            store.items.push("b".to_string());
+            Ok(())
+        })?;
+        let reloaded: Store = load(&path, &SCHEMA)?;
+        assert_eq!(reloaded.items, vec!["b".to_string()]);
+        let backups = std::fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().contains(".corrupt-"))
+            .count();
+        assert_eq!(backups, 1);
+
+        std::fs::write(&path, r#"{"version":3,"items":[]}"#)?;
+        assert!(load::<Store>(&path, &SCHEMA).is_err());
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 pub mod file_watcher;
 pub mod fs;
+pub mod json_store;
 pub mod path;
 pub mod pty;
 pub mod screen;
+pub mod store_writer;
 #[cfg(target_os = "windows")]
 pub mod windows;
 #[cfg(target_os = "windows")]
@@ -0,0 +1,36 @@
+//! One background thread for store writes that should not run while the task lock is held.
+//!
+//! Jobs run one at a time in the order they were queued, so writes to the same store keep
+//! their order.
+
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static QUEUE: OnceLock<Sender<Job>> = OnceLock::new();
+
+/// Runs `job` on the writer thread, starting it on first use.
+pub fn queue(job: impl FnOnce() + Send + 'static) {
+    let sender = QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        std::thread::spawn(move || {
+            for job in receiver {
+                job();
+            }
+        });
+        sender
+    });
+    if let Err(mpsc::SendError(job)) = sender.send(Box::new(job)) {
+        job();
+    }
+}
+
+/// Blocks until every job queued before the call has run.
+pub fn flush() {
+    let (done_tx, done_rx) = mpsc::channel();
+    queue(move || {
+        let _ = done_tx.send(());
+    });
+    let _ = done_rx.recv();
+}
@@ -37,6 +37,15 @@ pub fn to_wsl_path(path: &Path) -> Option<String> {
     None
 }
 
+/// `WSLENV` with `name` appended, so a variable set on a `wsl.exe` process reaches the Linux
+/// side.
+pub fn wslenv_with(name: &str) -> String {
+    match std::env::var("WSLENV") {
+        Ok(existing) if !existing.is_empty() => format!("{existing}:{name}"),
+        _ => name.to_string(),
+    }
+}
+
 pub fn bash_escape(value: &str) -> String {
     let mut escaped = String::from("'");
     for ch in value.chars() {
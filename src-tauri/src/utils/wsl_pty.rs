@@ -2,7 +2,7 @@ use crate::utils::pty::{
     ChildHandle, MasterHandle, ProcessExitStatus, ProcessHandle, ReadHandle, TerminalMaster,
     TerminalSize, WriteHandle,
 };
-use crate::utils::windows::{bash_escape, suppress_console_window, to_wsl_path};
+use crate::utils::windows::{bash_escape, suppress_console_window, to_wsl_path, wslenv_with};
 use anyhow::{anyhow, Context, Result};
 use parking_lot::Mutex;
 use std::io::{BufRead, BufReader, Read, Write};
@@ -57,10 +57,12 @@ impl ProcessHandle for WslChild {
     }
 }
 
+/// Runs `command` in a PTY inside WSL. `env` is set for the command through `WSLENV`.
 pub fn spawn_wsl_pty(
     worktree_path: &std::path::Path,
     command: &str,
     args: &[&str],
+    env: &[(&str, &str)],
     rows: u16,
     cols: u16,
     term: Option<&str>,
@@ -91,6 +93,13 @@ pub fn spawn_wsl_pty(
     let mut child = Command::new("wsl.exe");
     suppress_console_window(&mut child);
     child.args(["--cd", &wsl_path, "--", "bash", "-lc", &command_line]);
+    if !env.is_empty() {
+        for (name, value) in env {
+            child.env(*name, *value);
+        }
+        let names: Vec<&str> = env.iter().map(|(name, _)| *name).collect();
+        child.env("WSLENV", wslenv_with(&names.join(":")));
+    }
     child
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
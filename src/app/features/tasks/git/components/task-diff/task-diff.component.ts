@@ -38,6 +38,7 @@ import { DomSanitizer, SafeHtml } from "@angular/platform-browser";
 import { type Event as TauriEvent, type UnlistenFn } from "@tauri-apps/api/event";
 import { Subscription } from "rxjs";
 import {
+    DiffFile,
     DiffLineType,
     DiffMode,
     DiffPayload,
@@ -153,7 +154,7 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
     private watchVersion = 0;
     private reviewVersion = 0;
     private readonly emptyReviewStore: ReviewStore = {
-        version: 1,
+        version: 4,
         tasks: {},
     };
 
@@ -164,6 +165,9 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
     userDisplayName = "User";
     readonly reviewStatusOptions = REVIEW_STATUS_OPTIONS;
     private readonly collapsedThreads = new Set<string>();
+    private readonly expandedGeneratedFiles = new Set<string>();
+    /** Lines of expanded generated files, which the diff watch leaves out. */
+    private readonly generatedFileBodies = new Map<string, DiffFile>();
     private readonly commentDrafts = new Map<string, string>();
     readonly threadStatusUpdating = new Set<string>();
     readonly editingCommentIds = new Set<string>();
@@ -239,6 +243,8 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
         this.error = null;
         this.isLoading = false;
         this.hasLoaded = false;
+        this.expandedGeneratedFiles.clear();
+        this.generatedFileBodies.clear();
         if (!this.taskId) {
             return;
         }
@@ -268,9 +274,54 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
             this.hasLoaded = state.hasLoaded;
             this.cdr.detectChanges();
             this.diffViewport?.checkViewportSize();
+            void this.loadGeneratedFileBodies(watchVersion);
         });
     }
 
+    toggleGeneratedFile(row: RenderedDiffRow, event?: Event): void {
+        event?.stopPropagation();
+        if (this.expandedGeneratedFiles.has(row.filePath)) {
+            this.expandedGeneratedFiles.delete(row.filePath);
+        } else {
+            this.expandedGeneratedFiles.add(row.filePath);
+            void this.loadGeneratedFileBodies(this.watchVersion);
+        }
+        this.refreshRenderedRows();
+    }
+
+    /** Fetches the lines of the expanded generated files in the current diff. */
+    private async loadGeneratedFileBodies(watchVersion: number): Promise<void> {
+        const taskId = this.taskId;
+        const files = (this.diffPayload?.files ?? []).filter(
+            (file) =>
+                file.generated && this.expandedGeneratedFiles.has(file.path),
+        );
+        if (!taskId || !files.length) {
+            return;
+        }
+        try {
+            const bodies = await Promise.all(
+                files.map((file) =>
+                    this.taskStore.getDiffFile(
+                        taskId,
+                        file,
+                        false,
+                        this.diffMode,
+                    ),
+                ),
+            );
+            if (this.watchVersion !== watchVersion) {
+                return;
+            }
+            for (const body of bodies) {
+                this.generatedFileBodies.set(body.path, body);
+            }
+            this.refreshRenderedRows();
+        } catch (error) {
+            console.error("Failed to load generated file diff", error);
+        }
+    }
+
     private async stopDiffWatch(): Promise<void> {
         this.diffSubscription?.unsubscribe();
         this.diffSubscription = undefined;
@@ -385,12 +436,23 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
             if (!indexByPath.has(file.path)) {
                 indexByPath.set(file.path, rows.length);
             }
+            const collapsed =
+                file.generated && !this.expandedGeneratedFiles.has(file.path);
+            const body = file.generated
+                ? (this.generatedFileBodies.get(file.path) ?? file)
+                : file;
             rows.push({
                 kind: "header",
                 filePath: file.path,
                 status: file.status,
+                generated: file.generated,
+                collapsed,
+                truncated: body.truncated,
             });
-            for (const line of file.lines) {
+            if (collapsed) {
+                continue;
+            }
+            for (const line of body.lines) {
                 if (line.type === "meta") {
                     continue;
                 }
@@ -1452,6 +1514,11 @@ export class TaskDiffComponent implements OnChanges, OnDestroy {
         return this.threadStatusForKey(key);
     }
 
+    isThreadOutdated(row: RenderedDiffRow): boolean {
+        const key = this.draftKeyForRow(row);
+        return !!key && !!this.commentThreads.get(key)?.outdated;
+    }
+
     isThreadStatusUpdating(row: RenderedDiffRow): boolean {
         const key = this.draftKeyForRow(row);
         return !!key && this.threadStatusUpdating.has(key);
@@ -13,6 +13,11 @@ export interface RenderedDiffRow {
     filePath: string;
     displayName?: string;
     status?: string;
+    /** Header of a `linguist-generated` file; `collapsed` while its lines are hidden. */
+    generated?: boolean;
+    collapsed?: boolean;
+    /** Header of a file whose lines stop at the `[diff.limits]`. */
+    truncated?: boolean;
     line?: RenderedDiffLine;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
@@ -0,0 +1,20 @@
+/** A commit on a task's branch, with its change stats against its first parent. */
+export interface CommitInfo {
+    id: string;
+    shortId: string;
+    summary: string;
+    message: string;
+    authorName: string;
+    authorEmail: string;
+    time: string;
+    parentIds: string[];
+    filesChanged: number;
+    additions: number;
+    deletions: number;
+}
+
+/** The commits a "commits" diff compares: `to` against its first parent unless `from` is set. */
+export interface CommitRange {
+    from?: string | null;
+    to: string;
+}
@@ -0,0 +1,5 @@
+/** Unchanged lines around and between hunks; stage with the values the diff was loaded with. */
+export interface DiffContext {
+    contextLines?: number | null;
+    interhunkLines?: number | null;
+}
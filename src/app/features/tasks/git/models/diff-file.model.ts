@@ -4,4 +4,24 @@ export interface DiffFile {
     path: string;
     status: string;
     lines: DiffLine[];
+    /** The index holds changes to the file relative to HEAD. */
+    staged: boolean;
+    /** The worktree has changes the index does not have, or the file is untracked. */
+    unstaged: boolean;
+    /** Where the file was renamed or copied from. */
+    oldPath?: string | null;
+    /** Git treats the content as binary, so there are no lines. */
+    binary: boolean;
+    /** Size in bytes before the change; absent for added files. */
+    oldSize?: number | null;
+    /** Size in bytes after the change; absent for deleted files. */
+    newSize?: number | null;
+    /** The file mode changed, e.g. it became executable. */
+    modeChanged: boolean;
+    additions: number;
+    deletions: number;
+    /** Marked `linguist-generated` in `.gitattributes`; the diff view collapses it. */
+    generated: boolean;
+    /** `lines` stops short of the whole diff because the file is too large. */
+    truncated: boolean;
 }
@@ -1,8 +1,13 @@
 import { DiffLineType } from "./diff-line-type.model";
+import { DiffRange } from "./diff-range.model";
 
 export interface DiffLine {
     type: DiffLineType;
     content: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
+    /** Identifies the line's hunk by content, for staging, unstaging or discarding it. */
+    hunkId?: string | null;
+    /** Parts of a modified line that differ from the line paired with it. */
+    changeRanges?: DiffRange[] | null;
 }
@@ -1 +1,10 @@
-export type DiffMode = "worktree" | "branch";
+export type DiffMode = "worktree" | "branch" | "staged" | "unstaged" | "commits";
+
+/**
+ * Diff modes whose hunk ids staging and discarding accept. Both compare the worktree with the
+ * index; the worktree diff only matches while nothing of the file is staged.
+ */
+export const STAGEABLE_DIFF_MODES: readonly DiffMode[] = ["unstaged", "worktree"];
+
+/** Diff modes whose hunk ids unstaging accepts. */
+export const UNSTAGEABLE_DIFF_MODES: readonly DiffMode[] = ["staged"];
@@ -1,6 +1,17 @@
 import { DiffFile } from "./diff-file.model";
 
+export interface ReviewProgress {
+    filesTotal: number;
+    filesViewed: number;
+    threadsTotal: number;
+    threadsOpen: number;
+    threadsResolved: number;
+    verdict: "unreviewed" | "changes-requested" | "approved";
+}
+
 export interface DiffPayload {
     taskId: string;
     files: DiffFile[];
+    viewedPaths?: string[];
+    progress?: ReviewProgress;
 }
@@ -0,0 +1,5 @@
+/** A span of a diff line's content in UTF-16 code units, as string indexes count them. */
+export interface DiffRange {
+    start: number;
+    end: number;
+}
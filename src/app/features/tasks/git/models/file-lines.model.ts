@@ -0,0 +1,10 @@
+export type DiffSide = "old" | "new";
+
+export interface FileLines {
+    path: string;
+    /** Line number of the first entry in `lines`, counting from 1. */
+    start: number;
+    lines: string[];
+    /** Lines in the whole file; 0 when the file does not exist on that side. */
+    totalLines: number;
+}
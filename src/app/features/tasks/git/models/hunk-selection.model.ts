@@ -0,0 +1,10 @@
+export interface LineSelection {
+    lineNumberOld?: number | null;
+    lineNumberNew?: number | null;
+}
+
+/** A hunk of a file's diff by its `hunkId`; only the given changed lines when `lines` is set. */
+export interface HunkSelection {
+    hunkId: string;
+    lines?: LineSelection[];
+}
@@ -1,6 +1,11 @@
+export * from "./commit-info.model";
 export * from "./diff-changed-event.model";
+export * from "./diff-context.model";
 export * from "./diff-file.model";
 export * from "./diff-line.model";
 export * from "./diff-line-type.model";
 export * from "./diff-mode.model";
 export * from "./diff-payload.model";
+export * from "./diff-range.model";
+export * from "./file-lines.model";
+export * from "./hunk-selection.model";
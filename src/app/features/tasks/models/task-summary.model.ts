@@ -15,4 +15,8 @@ export interface TaskSummary {
     baseRepoPath: string;
     baseCommit: string;
     exitCode?: number | null;
+    autoRestart?: boolean;
+    restartCount?: number;
+    /** Status of a running review pass; `status` keeps what the task's agent ended with. */
+    reviewStatus?: TaskStatus | null;
 }
@@ -47,6 +47,7 @@ export class TaskDiffThreadComponent {
     @Input() draft = "";
     @Input() canSubmit = false;
     @Input() threadStatus: ReviewCommentStatus = "active";
+    @Input() outdated = false;
     @Input() isThreadStatusUpdating = false;
     @Input() editingCommentIds: ReadonlySet<string> = new Set();
     @Input() deletingCommentIds: ReadonlySet<string> = new Set();
@@ -71,11 +72,14 @@ export class TaskDiffThreadComponent {
 
     constructor(private readonly sanitizer: DomSanitizer) {}
 
-    displayNameFor(author: string): string {
-        if (author === "user") {
+    displayNameFor(comment: ReviewComment): string {
+        if (comment.author === "user") {
             return this.userDisplayName || "User";
         }
-        return author;
+        if (comment.agentKind && comment.agentKind !== comment.author) {
+            return `${comment.author} (${comment.agentKind})`;
+        }
+        return comment.author;
     }
 
     renderCommentBody(comment: ReviewComment): SafeHtml {
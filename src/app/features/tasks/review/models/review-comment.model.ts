@@ -1,6 +1,17 @@
+export interface ReviewSuggestion {
+    replacement: string;
+    appliedAt?: string | null;
+}
+
+export type ReviewAuthorKind = "user" | "agent";
+
 export interface ReviewComment {
     id: string;
     body: string;
     author: string;
+    authorEmail?: string | null;
+    authorKind?: ReviewAuthorKind;
+    agentKind?: string | null;
     createdAt: string;
+    suggestion?: ReviewSuggestion | null;
 }
@@ -1,6 +1,17 @@
 import { ReviewThread } from "./review-thread.model";
 
+export type ReviewVerdict = "unreviewed" | "changes-requested" | "approved";
+
+export interface ViewedFile {
+    blobId: string;
+    viewedAt: string;
+}
+
 export interface ReviewTaskEntry {
     taskId: string;
     threads: ReviewThread[];
+    verdict?: ReviewVerdict;
+    verdictBy?: string | null;
+    verdictAt?: string | null;
+    viewedFiles?: Record<string, ViewedFile>;
 }
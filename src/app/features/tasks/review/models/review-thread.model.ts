@@ -2,11 +2,31 @@ import { DiffLineType } from "../../git/models/diff-line-type.model";
 import { ReviewComment } from "./review-comment.model";
 import { ReviewCommentStatus } from "./review-comment-status.model";
 
+export type ReviewScope = "line" | "file" | "task";
+
 export interface ReviewThread {
+    id?: string;
+    scope?: ReviewScope;
     filePath: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
     lineType: DiffLineType;
+    range?: ReviewRange | null;
     status: ReviewCommentStatus;
     comments: ReviewComment[];
+    anchor?: ReviewAnchor | null;
+    outdated?: boolean;
+}
+
+export interface ReviewAnchor {
+    line: string;
+    contextBefore: string[];
+    contextAfter: string[];
+    lines?: string[];
+}
+
+export interface ReviewRange {
+    side: "old" | "new";
+    start: number;
+    end: number;
 }
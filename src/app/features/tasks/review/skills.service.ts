@@ -0,0 +1,38 @@
+import { Injectable, NgZone } from "@angular/core";
+import { AgentKind } from "../models";
+import { tauriInvoke } from "../../../shared/tauri/tauri-zone";
+
+export type SkillState = "not-installed" | "current" | "outdated" | "modified" | "unmanaged";
+
+export interface SkillStatus {
+    name: string;
+    agentKind: AgentKind;
+    dir: string;
+    state: SkillState;
+    installedVersion: string | null;
+}
+
+/** Limits a reinstall/uninstall to one skill and/or agent; everything when both are omitted. */
+export interface SkillSelection {
+    name?: string | null;
+    agentKind?: AgentKind | null;
+}
+
+@Injectable({
+    providedIn: "root",
+})
+export class SkillsService {
+    constructor(private readonly zone: NgZone) {}
+
+    async list(): Promise<SkillStatus[]> {
+        return tauriInvoke<SkillStatus[]>(this.zone, "skills_list");
+    }
+
+    async reinstall(req: SkillSelection = {}): Promise<SkillStatus[]> {
+        return tauriInvoke<SkillStatus[]>(this.zone, "skills_reinstall", { req });
+    }
+
+    async uninstall(req: SkillSelection = {}): Promise<SkillStatus[]> {
+        return tauriInvoke<SkillStatus[]>(this.zone, "skills_uninstall", { req });
+    }
+}
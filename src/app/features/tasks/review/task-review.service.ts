@@ -2,15 +2,19 @@ import { Injectable, NgZone } from "@angular/core";
 import {
     ReviewComment,
     ReviewCommentStatus,
+    ReviewRange,
+    ReviewScope,
     ReviewTaskEntry,
     ReviewThread,
     ReviewStore,
+    ReviewVerdict,
+    ViewedFile,
 } from "./models";
 import { DiffLineType } from "../git/models";
 import { tauriInvoke } from "../../../shared/tauri/tauri-zone";
 
 const DEFAULT_REVIEW_STORE: ReviewStore = {
-    version: 1,
+    version: 4,
     tasks: {},
 };
 
@@ -37,6 +41,26 @@ export class TaskReviewService {
         return tauriInvoke<ReviewComment>(this.zone, "task_review_edit_comment", { req });
     }
 
+    async applySuggestion(req: ApplyReviewSuggestionRequest): Promise<ReviewThread> {
+        return tauriInvoke<ReviewThread>(this.zone, "task_review_apply_suggestion", { req });
+    }
+
+    async setVerdict(req: SetReviewVerdictRequest): Promise<ReviewTaskEntry> {
+        return tauriInvoke<ReviewTaskEntry>(this.zone, "task_review_set_verdict", { req });
+    }
+
+    async setFileViewed(req: SetFileViewedRequest): Promise<ViewedFile | null> {
+        return tauriInvoke<ViewedFile | null>(this.zone, "task_review_set_file_viewed", { req });
+    }
+
+    async exportReview(req: ExportReviewRequest): Promise<ExportReviewResponse> {
+        return tauriInvoke<ExportReviewResponse>(this.zone, "task_review_export", { req });
+    }
+
+    async submitReview(req: SubmitReviewRequest): Promise<SubmitReviewResponse> {
+        return tauriInvoke<SubmitReviewResponse>(this.zone, "task_review_submit", { req });
+    }
+
     async deleteComment(
         req: DeleteReviewCommentRequest,
     ): Promise<DeleteReviewCommentResponse> {
@@ -93,17 +117,26 @@ export class TaskReviewService {
             threads: (entry?.threads ?? []).map((thread) =>
                 this.normalizeThread(thread),
             ),
+            verdict: entry?.verdict ?? "unreviewed",
+            verdictBy: entry?.verdictBy ?? null,
+            verdictAt: entry?.verdictAt ?? null,
+            viewedFiles: entry?.viewedFiles ?? {},
         };
     }
 
     private normalizeThread(thread: ReviewThread): ReviewThread {
         return {
+            id: thread.id,
+            scope: thread.scope ?? "line",
             filePath: thread.filePath,
             lineNumberOld: thread.lineNumberOld ?? null,
             lineNumberNew: thread.lineNumberNew ?? null,
             lineType: thread.lineType,
+            range: thread.range ?? null,
             status: thread.status ?? "active",
             comments: thread.comments ?? [],
+            anchor: thread.anchor ?? null,
+            outdated: thread.outdated ?? false,
         };
     }
 }
@@ -111,17 +144,22 @@ export class TaskReviewService {
 export interface AddReviewCommentRequest {
     worktreePath: string;
     taskId: string;
-    filePath: string;
+    threadId?: string | null;
+    scope?: ReviewScope;
+    filePath?: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
     lineType: DiffLineType;
+    range?: ReviewRange | null;
     body: string;
+    suggestion?: string | null;
 }
 
 export interface UpdateReviewThreadStatusRequest {
     worktreePath: string;
     taskId: string;
-    filePath: string;
+    threadId?: string | null;
+    filePath?: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
     status: ReviewCommentStatus;
@@ -135,17 +173,62 @@ export interface UpdateReviewThreadStatusResponse {
 export interface EditReviewCommentRequest {
     worktreePath: string;
     taskId: string;
-    filePath: string;
+    threadId?: string | null;
+    filePath?: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
     commentId: string;
     body: string;
+    suggestion?: string | null;
 }
 
-export interface DeleteReviewCommentRequest {
+export interface SetReviewVerdictRequest {
+    worktreePath: string;
+    taskId: string;
+    verdict: ReviewVerdict;
+}
+
+export interface SetFileViewedRequest {
     worktreePath: string;
     taskId: string;
     filePath: string;
+    viewed: boolean;
+}
+
+export interface ExportReviewRequest {
+    taskId: string;
+    format: "markdown" | "html";
+    outputPath?: string | null;
+}
+
+export interface ExportReviewResponse {
+    contents: string;
+    outputPath?: string | null;
+}
+
+export interface SubmitReviewRequest {
+    taskId: string;
+    cols?: number | null;
+    rows?: number | null;
+}
+
+export interface SubmitReviewResponse {
+    threadIds: string[];
+    startedAgent: boolean;
+}
+
+export interface ApplyReviewSuggestionRequest {
+    worktreePath: string;
+    taskId: string;
+    threadId: string;
+    commentId: string;
+}
+
+export interface DeleteReviewCommentRequest {
+    worktreePath: string;
+    taskId: string;
+    threadId?: string | null;
+    filePath?: string;
     lineNumberOld?: number | null;
     lineNumberNew?: number | null;
     commentId: string;
@@ -68,6 +68,16 @@ export class TaskSidebarComponent {
         return status.replace(/_/g, " ");
     }
 
+    /** A running review pass shows its own status, so approvals it waits on are visible. */
+    shownStatus(task: TaskSummary): TaskStatus {
+        return task.reviewStatus ?? task.status;
+    }
+
+    shownStatusLabel(task: TaskSummary): string {
+        const label = this.statusLabel(this.shownStatus(task));
+        return task.reviewStatus ? `REVIEW ${label}` : label;
+    }
+
     isRunning(status: TaskStatus): boolean {
         return (
             status === "IDLE" ||
@@ -6,7 +6,19 @@ import {
     BaseRepoInfo,
     TaskSummary,
 } from "./models";
-import { DiffMode, DiffPayload } from "./git/models";
+import {
+    CommitInfo,
+    CommitRange,
+    DiffContext,
+    DiffFile,
+    DiffMode,
+    DiffPayload,
+    DiffSide,
+    FileLines,
+    HunkSelection,
+    STAGEABLE_DIFF_MODES,
+    UNSTAGEABLE_DIFF_MODES,
+} from "./git/models";
 import {
     TerminalKind,
     TerminalExitEvent,
@@ -216,16 +228,106 @@ export class TaskStore {
         taskId: string,
         ignoreWhitespace = false,
         mode: DiffMode = "worktree",
+        context: DiffContext = {},
+        commits?: CommitRange,
     ): Promise<DiffPayload> {
         return tauriInvoke<DiffPayload>(this.zone, "task_git_diff_get", {
             req: {
                 taskId,
                 ignoreWhitespace,
                 mode,
+                commits,
+                ...context,
+            },
+        });
+    }
+
+    /** The diff's files with their stats but without lines; load those with `getDiffFile`. */
+    async getDiffSummary(
+        taskId: string,
+        ignoreWhitespace = false,
+        mode: DiffMode = "worktree",
+        commits?: CommitRange,
+    ): Promise<DiffPayload> {
+        return tauriInvoke<DiffPayload>(this.zone, "task_git_diff_summary", {
+            req: {
+                taskId,
+                ignoreWhitespace,
+                mode,
+                commits,
             },
         });
     }
 
+    async getDiffFile(
+        taskId: string,
+        file: Pick<DiffFile, "path" | "oldPath">,
+        ignoreWhitespace = false,
+        mode: DiffMode = "worktree",
+        full = false,
+        context: DiffContext = {},
+        commits?: CommitRange,
+    ): Promise<DiffFile> {
+        return tauriInvoke<DiffFile>(this.zone, "task_git_diff_file_get", {
+            req: {
+                taskId,
+                path: file.path,
+                oldPath: file.oldPath ?? null,
+                ignoreWhitespace,
+                mode,
+                full,
+                commits,
+                ...context,
+            },
+        });
+    }
+
+    /**
+     * The diff from its summary plus one `getDiffFile` call per file, so no single response
+     * carries every line. Generated files come without lines; load them when expanded.
+     */
+    async getDiffByFile(
+        taskId: string,
+        ignoreWhitespace = false,
+        mode: DiffMode = "worktree",
+    ): Promise<DiffPayload> {
+        const summary = await this.getDiffSummary(
+            taskId,
+            ignoreWhitespace,
+            mode,
+        );
+        const files = await Promise.all(
+            summary.files.map((file) =>
+                file.generated || file.binary
+                    ? file
+                    : this.getDiffFile(taskId, file, ignoreWhitespace, mode),
+            ),
+        );
+        return { ...summary, files };
+    }
+
+    /** Lines `start..=end` of a file on one side of the diff, or the whole file. */
+    async getFileLines(
+        taskId: string,
+        path: string,
+        side: DiffSide,
+        mode: DiffMode = "worktree",
+        start?: number,
+        end?: number,
+        commits?: CommitRange,
+    ): Promise<FileLines> {
+        return tauriInvoke<FileLines>(this.zone, "task_git_file_lines_get", {
+            req: { taskId, path, side, mode, start, end, commits },
+        });
+    }
+
+    /** Commits on the task's branch since its base commit, newest first. */
+    async listCommits(taskId: string): Promise<CommitInfo[]> {
+        return tauriInvoke<CommitInfo[]>(this.zone, "task_git_list_commits", {
+            req: { taskId },
+        });
+    }
+
     async hasUncommittedChanges(taskId: string): Promise<boolean> {
         return tauriInvoke<boolean>(this.zone, "task_git_has_changes", {
             req: { taskId },
@@ -245,7 +347,7 @@ export class TaskStore {
             taskId,
             mode,
             refreshDelayMs: this.diffRefreshDelayMs,
-            getDiff: (id, diffMode) => this.getDiff(id, false, diffMode),
+            getDiff: (id, diffMode) => this.getDiffByFile(id, false, diffMode),
             startDiffWatch: (id) => this.startDiffWatch(id),
             stopDiffWatch: (id) => this.stopDiffWatch(id),
             listen: <T>(
@@ -261,6 +363,54 @@ export class TaskStore {
         };
     }
 
+    /** `mode` is the diff the hunk ids were read from; see `STAGEABLE_DIFF_MODES`. */
+    async stageHunks(
+        taskId: string,
+        path: string,
+        hunks: HunkSelection[],
+        mode: DiffMode,
+        context: DiffContext = {},
+    ): Promise<void> {
+        if (!STAGEABLE_DIFF_MODES.includes(mode)) {
+            throw new Error(`Hunks of the ${mode} diff cannot be staged.`);
+        }
+        await tauriInvoke<void>(this.zone, "task_git_stage", {
+            req: { taskId, path, hunks, ...context },
+        });
+    }
+
+    /** `mode` is the diff the hunk ids were read from; see `UNSTAGEABLE_DIFF_MODES`. */
+    async unstageHunks(
+        taskId: string,
+        path: string,
+        hunks: HunkSelection[],
+        mode: DiffMode,
+        context: DiffContext = {},
+    ): Promise<void> {
+        if (!UNSTAGEABLE_DIFF_MODES.includes(mode)) {
+            throw new Error(`Hunks of the ${mode} diff cannot be unstaged.`);
+        }
+        await tauriInvoke<void>(this.zone, "task_git_unstage", {
+            req: { taskId, path, hunks, ...context },
+        });
+    }
+
+    /** `mode` is the diff the hunk ids were read from; see `STAGEABLE_DIFF_MODES`. */
+    async discardHunks(
+        taskId: string,
+        path: string,
+        hunks: HunkSelection[],
+        mode: DiffMode,
+        context: DiffContext = {},
+    ): Promise<void> {
+        if (!STAGEABLE_DIFF_MODES.includes(mode)) {
+            throw new Error(`Hunks of the ${mode} diff cannot be discarded.`);
+        }
+        await tauriInvoke<void>(this.zone, "task_git_discard", {
+            req: { taskId, path, hunks, ...context },
+        });
+    }
+
     async commitTask(
         taskId: string,
         message: string,
@@ -2,4 +2,9 @@ export interface TimeTrackingEntry {
     branchName: string;
     title?: string | null;
     byDate: Record<string, number>;
+    taskId?: string | null;
+    byCategory?: Partial<Record<TimeCategory, Record<string, number>>>;
+    byTask?: Record<string, Partial<Record<TimeCategory, Record<string, number>>>>;
 }
+
+export type TimeCategory = "agent" | "awaiting-approval" | "human";
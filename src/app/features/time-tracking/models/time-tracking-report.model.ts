@@ -0,0 +1,19 @@
+import { TimeCategory } from "./time-tracking-entry.model";
+
+export type TimeTrackingReportGroupBy = "branch" | "task" | "day" | "week" | "month";
+
+export type TimeTrackingExportFormat = "csv" | "ical";
+
+export interface TimeTrackingReportRow {
+    key: string;
+    label: string;
+    openSeconds: number;
+    secondsByCategory: Partial<Record<TimeCategory, number>>;
+}
+
+export interface TimeTrackingReport {
+    range: { from?: string | null; to?: string | null };
+    groupBy: TimeTrackingReportGroupBy;
+    rows: TimeTrackingReportRow[];
+    totals: TimeTrackingReportRow;
+}